@@ -60,10 +60,16 @@ echo -e "${GREEN}✅ Contract built successfully${NC}"
 # Step 5: Deploy contracts
 echo -e "${YELLOW}🚀 Step 5: Deploying contracts...${NC}"
 
+# Player chains configure themselves from the application parameters
+DEPLOY_LEADERBOARD_CHAIN=$(grep -o '"leaderboardChainId": "[^"]*"' tmp/dev-config.json | cut -d'"' -f4)
+# Admin-only operations must be signed by this key (e.g. from `linera keygen`), none if unset
+DEPLOY_ADMIN_OWNER=${ADMIN_OWNER:-}
+
 # Deploy application with user
 echo "Deploying application with admin user..."
 APP_ID=$(linera --with-wallet 1 publish-and-create \
   target/wasm32-unknown-unknown/release/flappy_{contract,service}.wasm \
+  --json-parameters "{\"leaderboard_chain_id\": \"$DEPLOY_LEADERBOARD_CHAIN\", \"admin_owners\": [${DEPLOY_ADMIN_OWNER:+\"$DEPLOY_ADMIN_OWNER\"}]}" \
   --json-argument '{
     "player_name": "LEADERBOARD_CHAIN",
     "admin_username": "xxx",
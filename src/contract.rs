@@ -3,14 +3,25 @@
 mod state;
 
 use linera_sdk::{
-    linera_base_types::{ChainId, WithContractAbi},
+    linera_base_types::{AccountOwner, ChainId, StreamName, StreamUpdate, WithContractAbi},
     views::{RootView, View},
     Contract, ContractRuntime,
 };
 
 use flappy::{
-    ApplicationParameters, FlappyMessage, InstantiationArgument, LoginResult, Operation,
-    PracticeEntry, Tournament, TournamentResult, TournamentStatus, User, UserRole,
+    decode_ghost_frames, derive_credential_salt, encode_ghost_frames, hash_reset_token,
+    head_to_head_key, insert_practice_top_entry, migration_batch, normalize_username,
+    rank_tournament_results, score_achievements, submission_allowed, validate_username,
+    win_streaks, AccountRestriction, Achievement, AchievementKind, ApplicationParameters,
+    BoardsSnapshot, Challenge, ChallengeStatus, Credential, CredentialReset, FlappyEvent,
+    FlappyMessage, Friend, FriendRequest, GhostRun, InstantiationArgument, LeaderboardMove,
+    LeaderboardMoveStep, LoginResult, Operation, PendingScore, PracticeEntry, PracticeRun,
+    PracticeRunsSummary, RestrictionKind, RunTicket, Tournament, TournamentResult,
+    TournamentSnapshot, TournamentStatus, User, UserProfile, UserRole, UserSnapshot,
+    CREDENTIAL_RESET_TTL, DEFAULT_PRACTICE_HISTORY_CAP, DEFAULT_RESERVED_USERNAMES,
+    FLAPPY_STREAM_NAME, MAX_GHOST_RUN_BYTES, MAX_PRACTICE_HISTORY_CAP, MIGRATION_BATCH_SIZE,
+    PRACTICE_SUMMARY_BATCH_SIZE, SCHEMA_VERSION, TOURNAMENTS_ENTERED_MILESTONE,
+    WIN_STREAK_MILESTONE,
 };
 
 use self::state::FlappyState;
@@ -18,6 +29,7 @@ use self::state::FlappyState;
 pub struct FlappyContract {
     state: FlappyState,
     runtime: ContractRuntime<Self>,
+    forwarded_origin: Option<ChainId>, // Origin of the message a former leaderboard chain passed on
 }
 
 linera_sdk::contract!(FlappyContract);
@@ -30,28 +42,50 @@ impl Contract for FlappyContract {
     type Message = FlappyMessage;
     type Parameters = ApplicationParameters;
     type InstantiationArgument = InstantiationArgument;
-    type EventValue = ();
+    type EventValue = FlappyEvent;
 
     async fn load(runtime: ContractRuntime<Self>) -> Self {
         let state = FlappyState::load(runtime.root_view_storage_context())
             .await
             .expect("Failed to load state");
-        FlappyContract { state, runtime }
+        FlappyContract {
+            state,
+            runtime,
+            forwarded_origin: None,
+        }
     }
 
     async fn instantiate(&mut self, args: Self::InstantiationArgument) {
         self.state.player_name.set(args.player_name);
-        self.state.top_leaderboard.set(Vec::new());
+        self.state.schema_version.set(SCHEMA_VERSION);
         self.state.is_leaderboard_chain.set(false);
         self.state.leaderboard_chain_id.set(None);
 
+        let parameters = self.runtime.application_parameters();
+        if !parameters.game.is_playable() {
+            panic!(
+                "Game constants must make every run take time: {:?}",
+                parameters.game
+            );
+        }
+        self.configure_from_parameters();
+
         // Initialize user management fields
         self.state.current_user.set(None);
+        self.state.reserved_usernames.set(
+            DEFAULT_RESERVED_USERNAMES
+                .iter()
+                .map(|name| name.to_string())
+                .collect(),
+        );
 
         // Initialize practice mode fields
         self.state.practice_leaderboard.set(Vec::new());
         self.state.my_practice_scores.set(Vec::new());
         self.state.my_practice_best.set(0);
+        self.state
+            .practice_history_cap
+            .set(DEFAULT_PRACTICE_HISTORY_CAP);
 
         // Initialize tournament fields
         self.state.pinned_tournaments.set(Vec::new());
@@ -60,24 +94,43 @@ impl Contract for FlappyContract {
 
         // Create admin user if provided (only for leaderboard chain setup)
         if let (Some(admin_username), Some(admin_hash)) = (args.admin_username, args.admin_hash) {
-            let admin_user = User {
+            let mut admin_user = User {
                 username: admin_username.clone(),
-                hash: admin_hash,
+                hash: String::new(),
                 role: UserRole::Admin,
                 created_at: self.runtime.system_time().micros(),
                 chain_id: Some(self.runtime.chain_id()),
             };
+            self.set_credential(&mut admin_user, &admin_hash);
 
             self.state
                 .users
                 .insert(&admin_username, admin_user)
                 .expect("Failed to create admin user");
+            self.index_username(&admin_username);
         }
     }
 
     async fn execute_operation(&mut self, operation: Self::Operation) -> Self::Response {
-        // Auto-update tournament statuses based on time before processing any operation
-        if *self.state.is_leaderboard_chain.get() {
+        self.configure_from_parameters();
+        self.migrate_state().await;
+
+        // A moving leaderboard chain only takes the operations continuing the move,
+        // anything else would change state that may already be sent
+        if self.leaderboard_move_in_progress()
+            && !matches!(
+                operation,
+                Operation::MoveLeaderboardChain { .. }
+                    | Operation::CancelLeaderboardMove
+                    | Operation::SetupGame { .. }
+            )
+        {
+            panic!("Leaderboard chain is moving to a new chain");
+        }
+
+        // Auto-update tournament statuses based on time before processing any operation.
+        // Not while moving, the tournaments may already be on their way to the new chain
+        if *self.state.is_leaderboard_chain.get() && !self.leaderboard_move_in_progress() {
             self.update_tournament_statuses().await;
         }
 
@@ -86,19 +139,11 @@ impl Contract for FlappyContract {
                 leaderboard_chain_id,
                 leaderboard_name,
             } => {
-                // Only allow setup if not already configured
-                if self.state.leaderboard_chain_id.get().is_some() {
-                    panic!("Game already configured. Leaderboard chain is already set.");
-                }
-
-                // Set the leaderboard chain ID for all chains
-                self.state
-                    .leaderboard_chain_id
-                    .set(Some(leaderboard_chain_id));
-
-                // If this chain is being designated as the leaderboard chain
-                if self.runtime.chain_id() == leaderboard_chain_id {
-                    self.state.is_leaderboard_chain.set(true);
+                // Chains of deployments without a leaderboard chain parameter are configured here,
+                // the others only set their player name. Configured chains follow leaderboard chain
+                // moves through its events, so a client's outdated chain ID is ignored
+                if self.state.leaderboard_chain_id.get().is_none() {
+                    self.configure_leaderboard_chain(leaderboard_chain_id);
                 }
 
                 self.state.player_name.set(leaderboard_name);
@@ -107,84 +152,23 @@ impl Contract for FlappyContract {
                 // from the json-argument, so we don't need to update it here
             }
 
-            Operation::LoginOrRegister {
-                username,
-                hash,
-                requester_chain_id,
-            } => {
-                // Only works on leaderboard chain
-                if !*self.state.is_leaderboard_chain.get() {
-                    let result = LoginResult {
-                        success: false,
-                        user: None,
-                        message: "User management only available on leaderboard chain".to_string(),
-                        is_new_user: false,
-                    };
-                    self.state
-                        .login_results
-                        .insert(&requester_chain_id, result)
-                        .expect("Failed to store login result");
-                    return;
-                }
-
-                let result = match self.state.users.get(&username).await {
-                    Ok(Some(existing_user)) => {
-                        // User exists - check password
-                        if existing_user.hash == hash {
-                            // Login successful
-                            LoginResult {
-                                success: true,
-                                user: Some(existing_user),
-                                message: "Login successful".to_string(),
-                                is_new_user: false,
-                            }
-                        } else {
-                            // Wrong password
-                            LoginResult {
-                                success: false,
-                                user: None,
-                                message: "Invalid password".to_string(),
-                                is_new_user: false,
-                            }
-                        }
-                    }
-                    _ => {
-                        // User doesn't exist - register new user
-                        let new_user = User {
-                            username: username.clone(),
-                            hash,
-                            role: UserRole::Player, // Default role
-                            created_at: self.runtime.system_time().micros(),
-                            chain_id: Some(requester_chain_id),
-                        };
-
-                        match self.state.users.insert(&username, new_user.clone()) {
-                            Ok(_) => LoginResult {
-                                success: true,
-                                user: Some(new_user),
-                                message: "User registered successfully".to_string(),
-                                is_new_user: true,
-                            },
-                            Err(_) => LoginResult {
-                                success: false,
-                                user: None,
-                                message: "Failed to register user".to_string(),
-                                is_new_user: false,
-                            },
-                        }
-                    }
-                };
-
-                // Store result for the specific requesting chain
+            Operation::LoginOrRegister { username, hash } => {
+                // Every chain, the leaderboard chain included, logs in as itself by message,
+                // so the leaderboard chain knows which chain asked. The chain's session ends
+                // until the reply arrives, so its result is never a stale one
+                let chain_id = self.runtime.chain_id();
+                self.state.current_user.set(None);
                 self.state
                     .login_results
-                    .insert(&requester_chain_id, result)
-                    .expect("Failed to store login result");
+                    .remove(&chain_id)
+                    .expect("Failed to clear login result");
+                self.send_to_leaderboard_chain(FlappyMessage::LoginOrRegister { username, hash });
             }
 
             Operation::DeleteUser {
                 caller_chain_id,
                 username,
+                purge_scores,
             } => {
                 // Only admins can delete users, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
@@ -192,7 +176,7 @@ impl Contract for FlappyContract {
                 }
 
                 // Validate admin session
-                let _admin_user = self
+                let admin_user = self
                     .validate_admin_session(caller_chain_id)
                     .await
                     .unwrap_or_else(|msg| panic!("{}", msg));
@@ -205,37 +189,8 @@ impl Contract for FlappyContract {
                             panic!("Cannot delete admin users");
                         }
 
-                        // Remove user from users map (but keep their scores)
-                        self.state
-                            .users
-                            .remove(&username)
-                            .expect("Failed to delete user");
-
-                        // Clean up user's login results for all chains if any exist
-                        let chain_ids = self
-                            .state
-                            .login_results
-                            .indices()
-                            .await
-                            .expect("Failed to get login result chain IDs");
-
-                        for chain_id in chain_ids {
-                            if let Ok(Some(login_result)) =
-                                self.state.login_results.get(&chain_id).await
-                            {
-                                if let Some(result_user) = &login_result.user {
-                                    if result_user.username == username {
-                                        self.state
-                                            .login_results
-                                            .remove(&chain_id)
-                                            .expect("Failed to remove login result");
-                                    }
-                                }
-                            }
-                        }
-
-                        // Note: Practice scores and tournament scores are preserved
-                        // Note: Tournament participation history is preserved
+                        self.soft_delete_user(&username, &admin_user.username, purge_scores)
+                            .await;
                     }
                     _ => {
                         panic!("User '{}' not found", username);
@@ -243,536 +198,3610 @@ impl Contract for FlappyContract {
                 }
             }
 
-            Operation::SubmitPracticeScore { username, score } => {
-                // Process on player chains (any chain can submit practice scores)
-
-                // Add score to personal history
-                let mut my_scores = self.state.my_practice_scores.get().clone();
-                my_scores.push(score);
-                self.state.my_practice_scores.set(my_scores);
-
-                // Check if this is a new personal best
-                let current_best = *self.state.my_practice_best.get();
-                if score > current_best {
-                    self.state.my_practice_best.set(score);
-
-                    // Send message to leaderboard chain if configured
-                    if let Some(leaderboard_id) = self.state.leaderboard_chain_id.get() {
-                        let message = FlappyMessage::UpdatePracticeBest {
-                            username,
-                            score,
-                            player_chain_id: self.runtime.chain_id(),
-                        };
+            Operation::DeleteMyAccount {
+                username,
+                hash,
+                purge_scores,
+            } => {
+                // Only works on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Accounts can only be deleted on the leaderboard chain");
+                }
 
-                        self.runtime
-                            .prepare_message(message)
-                            .send_to(*leaderboard_id);
-                    }
+                // Caller must know the account's credential
+                let mut user = self
+                    .state
+                    .users
+                    .get(&username)
+                    .await
+                    .expect("Failed to get user")
+                    .unwrap_or_else(|| panic!("User '{}' not found", username));
+                if !self.verify_credential(&mut user, &hash).await {
+                    panic!("Invalid password");
+                }
+                if user.role == UserRole::Admin {
+                    panic!("Cannot delete admin users");
                 }
+
+                self.soft_delete_user(&username, &username, purge_scores)
+                    .await;
             }
 
-            // Tournament management operations
-            Operation::CreateTournament {
+            Operation::SetModerationThresholds {
                 caller_chain_id,
-                name,
-                description,
-                start_time,
-                end_time,
+                thresholds,
             } => {
-                // Only admins can create tournaments, only on leaderboard chain
+                // Only admins can tune moderation, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be created on the leaderboard chain");
+                    panic!("Moderation thresholds can only be set on the leaderboard chain");
                 }
 
                 // Validate admin session
-                let admin_user = self
-                    .validate_admin_session(caller_chain_id)
+                self.validate_admin_session(caller_chain_id)
                     .await
                     .unwrap_or_else(|msg| panic!("{}", msg));
 
-                // Generate unique tournament ID
-                let counter = *self.state.tournament_counter.get();
-                let tournament_id = format!("tournament_{}", counter);
-                self.state.tournament_counter.set(counter + 1);
-
-                // Validate time constraints (comparing raw seconds before conversion)
-                if let (Some(start), Some(end)) = (start_time, end_time) {
-                    if end <= start {
-                        panic!("End time must be after start time");
-                    }
-                }
-
-                // Create tournament - convert user-provided timestamps from seconds to microseconds
-                let tournament = Tournament {
-                    id: tournament_id.clone(),
-                    name,
-                    description,
-                    creator: admin_user.username.clone(), // Use authenticated admin's username
-                    status: TournamentStatus::Registration,
-                    start_time: start_time.map(|t| t * 1_000_000), // Convert seconds to microseconds
-                    end_time: end_time.map(|t| t * 1_000_000), // Convert seconds to microseconds
-                    participants: Vec::new(),
-                    results: Vec::new(),
-                    created_at: self.runtime.system_time().micros(),
-                    is_pinned: false,
-                    pinned_at: None,
-                    pinned_by: None,
-                };
-
-                self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to create tournament");
+                self.state.moderation_thresholds.set(thresholds);
             }
 
-            Operation::JoinTournament {
-                tournament_id,
-                username,
+            Operation::ApproveScore {
+                caller_chain_id,
+                pending_id,
             } => {
-                // Tournament joining can happen on any chain, but data is stored on leaderboard chain
+                // Only admins can review scores, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournament joining must be done on the leaderboard chain");
+                    panic!("Scores can only be reviewed on the leaderboard chain");
                 }
 
-                // Get tournament and validate
-                let mut tournament = self
-                    .state
-                    .tournaments
-                    .get(&tournament_id)
+                // Validate admin session
+                self.validate_admin_session(caller_chain_id)
                     .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
+                    .unwrap_or_else(|msg| panic!("{}", msg));
 
-                if tournament.status == TournamentStatus::Ended {
-                    panic!("Cannot join tournament that has already ended");
+                let pending = self.take_pending_score(&pending_id).await;
+                if self.active_restriction(&pending.username).await.is_some() {
+                    panic!("User '{}' is restricted", pending.username);
                 }
 
-                if tournament.participants.contains(&username) {
-                    panic!("User already joined this tournament");
+                match pending.tournament_id {
+                    Some(tournament_id) => {
+                        let is_active = matches!(
+                            self.state.tournaments.get(&tournament_id).await,
+                            Ok(Some(Tournament {
+                                status: TournamentStatus::Active,
+                                ..
+                            }))
+                        );
+                        if !is_active {
+                            panic!("Tournament '{}' is no longer active", tournament_id);
+                        }
+                        self.apply_tournament_score(
+                            tournament_id,
+                            pending.username,
+                            pending.score,
+                            pending.chain_id,
+                            pending.ghost,
+                        )
+                        .await;
+                    }
+                    None => {
+                        // Another approved or clean score may have beaten it meanwhile
+                        let current_best = self
+                            .state
+                            .practice_best_scores
+                            .get(&pending.username)
+                            .await
+                            .expect("Failed to get practice best score")
+                            .map_or(0, |entry| entry.score);
+                        if pending.score > current_best {
+                            self.apply_practice_best(
+                                pending.username,
+                                pending.score,
+                                pending.chain_id,
+                                pending.ghost,
+                            )
+                            .await;
+                        }
+                    }
                 }
-
-                // Add user to tournament
-                tournament.participants.push(username);
-                self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to update tournament");
             }
 
-            Operation::StartTournament {
+            Operation::RejectScore {
                 caller_chain_id,
-                tournament_id,
+                pending_id,
             } => {
-                // Only admins can start tournaments, only on leaderboard chain
+                // Only admins can review scores, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be started on the leaderboard chain");
+                    panic!("Scores can only be reviewed on the leaderboard chain");
                 }
 
                 // Validate admin session
-                let _admin_user = self
-                    .validate_admin_session(caller_chain_id)
+                self.validate_admin_session(caller_chain_id)
                     .await
                     .unwrap_or_else(|msg| panic!("{}", msg));
 
-                let mut tournament = self
-                    .state
-                    .tournaments
-                    .get(&tournament_id)
-                    .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
-
-                if tournament.status != TournamentStatus::Registration {
-                    panic!("Tournament is not in registration phase");
-                }
-
-                tournament.status = TournamentStatus::Active;
-                if tournament.start_time.is_none() {
-                    tournament.start_time = Some(self.runtime.system_time().micros());
-                }
-
-                self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to update tournament");
+                self.take_pending_score(&pending_id).await;
             }
 
-            Operation::EndTournament {
+            Operation::BanUser {
                 caller_chain_id,
-                tournament_id,
-                results,
+                username,
+                reason,
+                duration,
+                hide_scores,
             } => {
-                // Only admins can end tournaments, only on leaderboard chain
+                // Only admins can ban users, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be ended on the leaderboard chain");
+                    panic!("Users can only be banned on the leaderboard chain");
                 }
 
                 // Validate admin session
-                let _admin_user = self
+                let admin_user = self
                     .validate_admin_session(caller_chain_id)
                     .await
                     .unwrap_or_else(|msg| panic!("{}", msg));
 
-                let mut tournament = self
+                let user = self
                     .state
-                    .tournaments
-                    .get(&tournament_id)
+                    .users
+                    .get(&username)
                     .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
-
-                if tournament.status != TournamentStatus::Active {
-                    panic!("Tournament is not active");
+                    .expect("Failed to get user")
+                    .unwrap_or_else(|| panic!("User '{}' not found", username));
+                if user.role == UserRole::Admin {
+                    panic!("Cannot ban admin users");
+                }
+                if self.is_deleted(&username).await {
+                    panic!("User '{}' has been deleted", username);
                 }
 
-                tournament.status = TournamentStatus::Ended;
-                tournament.end_time = Some(self.runtime.system_time().micros());
-                tournament.results = results;
-
+                let issued_at = self.runtime.system_time().micros();
+                let (kind, expires_at) = match duration {
+                    Some(0) => panic!("Suspension duration must be positive"),
+                    Some(seconds) => (
+                        RestrictionKind::Suspended,
+                        Some(issued_at + seconds * 1_000_000), // Convert seconds to microseconds
+                    ),
+                    None => (RestrictionKind::Banned, None),
+                };
+                let restriction = AccountRestriction {
+                    kind,
+                    reason,
+                    issued_by: admin_user.username,
+                    issued_at,
+                    expires_at,
+                    scores_hidden: hide_scores,
+                };
                 self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to update tournament");
+                    .account_restrictions
+                    .insert(&username, restriction)
+                    .expect("Failed to store account restriction");
+
+                self.clear_login_sessions(&username).await;
+                self.reset_practice_leaderboard().await;
+                if hide_scores {
+                    self.hide_tournament_results(&username).await;
+                } else {
+                    self.restore_tournament_results(&username).await;
+                }
             }
 
-            Operation::PinTournament {
+            Operation::UnbanUser {
                 caller_chain_id,
-                tournament_id,
-                pin,
+                username,
             } => {
-                // Only admins can pin tournaments, only on leaderboard chain
+                // Only admins can lift bans, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be pinned on the leaderboard chain");
+                    panic!("Bans can only be lifted on the leaderboard chain");
                 }
 
                 // Validate admin session
-                let admin_user = self
-                    .validate_admin_session(caller_chain_id)
+                self.validate_admin_session(caller_chain_id)
                     .await
                     .unwrap_or_else(|msg| panic!("{}", msg));
 
-                let mut tournament = self
-                    .state
-                    .tournaments
-                    .get(&tournament_id)
-                    .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
-
-                tournament.is_pinned = pin;
-                if pin {
-                    tournament.pinned_at = Some(self.runtime.system_time().micros());
-                    tournament.pinned_by = Some(admin_user.username.clone()); // Use authenticated admin's username
-
-                    // Add to pinned list if not already there
-                    let mut pinned = self.state.pinned_tournaments.get().clone();
-                    if !pinned.contains(&tournament_id) {
-                        pinned.push(tournament_id.clone());
-                        self.state.pinned_tournaments.set(pinned);
+                match self.state.account_restrictions.get(&username).await {
+                    Ok(Some(restriction)) if restriction.kind == RestrictionKind::Deleted => {
+                        panic!("Deleted accounts cannot be restored");
                     }
-                } else {
-                    tournament.pinned_at = None;
-                    tournament.pinned_by = None;
-
-                    // Remove from pinned list
-                    let mut pinned = self.state.pinned_tournaments.get().clone();
-                    pinned.retain(|id| id != &tournament_id);
-                    self.state.pinned_tournaments.set(pinned);
+                    Ok(Some(_)) => {
+                        self.state
+                            .account_restrictions
+                            .remove(&username)
+                            .expect("Failed to remove account restriction");
+                    }
+                    _ => panic!("User '{}' is not banned", username),
                 }
 
-                self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to update tournament");
+                self.reset_practice_leaderboard().await;
+                self.restore_tournament_results(&username).await;
             }
 
-            Operation::DeleteTournament {
+            Operation::ChangeCredential {
                 caller_chain_id,
-                tournament_id,
+                username,
+                old_hash,
+                new_hash,
             } => {
-                // Only admins can delete tournaments, only on leaderboard chain
+                // Only works on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be deleted on the leaderboard chain");
+                    panic!("Credentials can only be changed on the leaderboard chain");
                 }
 
-                // Validate admin session
-                let _admin_user = self
-                    .validate_admin_session(caller_chain_id)
-                    .await
-                    .unwrap_or_else(|msg| panic!("{}", msg));
+                // Caller must be logged in as this user
+                let session_user = match self.state.login_results.get(&caller_chain_id).await {
+                    Ok(Some(LoginResult {
+                        success: true,
+                        user: Some(user),
+                        ..
+                    })) => user,
+                    _ => panic!("No login session found - please login first"),
+                };
+                if session_user.username != username {
+                    panic!("Credentials can only be changed by the account owner");
+                }
 
-                let _tournament = self
+                let mut user = self
                     .state
-                    .tournaments
-                    .get(&tournament_id)
+                    .users
+                    .get(&username)
                     .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
+                    .expect("Failed to get user")
+                    .unwrap_or_else(|| panic!("User '{}' not found", username));
+                if !self.verify_credential(&mut user, &old_hash).await {
+                    panic!("Invalid password");
+                }
 
-                // Remove from tournaments map
+                self.set_credential(&mut user, &new_hash);
                 self.state
-                    .tournaments
-                    .remove(&tournament_id)
-                    .expect("Failed to delete tournament");
+                    .users
+                    .insert(&username, user)
+                    .expect("Failed to update user");
 
-                // Remove from pinned list if present
-                let mut pinned = self.state.pinned_tournaments.get().clone();
-                pinned.retain(|id| id != &tournament_id);
-                self.state.pinned_tournaments.set(pinned);
+                // Existing sessions must log in again with the new credential
+                self.clear_login_sessions(&username).await;
             }
 
-            Operation::UpdateTournament {
-                caller_chain_id,
-                tournament_id,
-                name,
-                description,
-                start_time,
-                end_time,
+            Operation::IssueCredentialReset {
+                username,
+                token_hash,
             } => {
-                // Only admins can update tournaments, only on leaderboard chain
+                // Only admins can issue resets, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    panic!("Tournaments can only be updated on the leaderboard chain");
+                    panic!("Credential resets can only be issued on the leaderboard chain");
                 }
 
-                // Validate admin session
-                let _admin_user = self
-                    .validate_admin_session(caller_chain_id)
-                    .await
-                    .unwrap_or_else(|msg| panic!("{}", msg));
+                let admin_owner = self
+                    .admin_signer()
+                    .unwrap_or_else(|| panic!("Credential resets must be signed by an admin key"));
 
-                let mut tournament = self
+                let user = self
                     .state
-                    .tournaments
-                    .get(&tournament_id)
+                    .users
+                    .get(&username)
                     .await
-                    .expect("Failed to get tournament")
-                    .expect("Tournament not found");
+                    .expect("Failed to get user")
+                    .unwrap_or_else(|| panic!("User '{}' not found", username));
 
-                // Validate time constraints if both provided (comparing raw seconds before conversion)
-                if let (Some(start), Some(end)) = (start_time, end_time) {
-                    if end <= start {
-                        panic!("End time must be after start time");
+                let issued_at = self.runtime.system_time().micros();
+                let reset = CredentialReset {
+                    token_hash: token_hash.to_lowercase(),
+                    issued_by: admin_owner.to_string(),
+                    issued_at,
+                    expires_at: issued_at + CREDENTIAL_RESET_TTL,
+                };
+
+                // A single admin key can't take over another admin account: the first
+                // key parks the reset until a different key issues the same token
+                if user.role == UserRole::Admin {
+                    let pending = self
+                        .state
+                        .pending_admin_resets
+                        .get(&username)
+                        .await
+                        .expect("Failed to get pending admin reset");
+                    let approved = pending.is_some_and(|pending| {
+                        pending.token_hash == reset.token_hash
+                            && pending.issued_by != reset.issued_by
+                            && pending.expires_at > issued_at
+                    });
+                    if !approved {
+                        self.state
+                            .pending_admin_resets
+                            .insert(&username, reset)
+                            .expect("Failed to store pending admin reset");
+                        return;
                     }
+                    self.state
+                        .pending_admin_resets
+                        .remove(&username)
+                        .expect("Failed to remove pending admin reset");
                 }
 
-                // Update fields - convert user-provided timestamps from seconds to microseconds
-                if let Some(name) = name {
-                    tournament.name = name;
-                }
-                if let Some(description) = description {
-                    tournament.description = description;
+                self.state
+                    .credential_resets
+                    .insert(&username, reset)
+                    .expect("Failed to store credential reset");
+            }
+
+            Operation::ResetCredential {
+                username,
+                token,
+                new_hash,
+            } => {
+                // Only works on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Credentials can only be reset on the leaderboard chain");
                 }
-                if let Some(start_time) = start_time {
-                    tournament.start_time = Some(start_time * 1_000_000); // Convert seconds to microseconds
+
+                let reset = self
+                    .state
+                    .credential_resets
+                    .get(&username)
+                    .await
+                    .expect("Failed to get credential reset")
+                    .unwrap_or_else(|| panic!("No credential reset pending for '{}'", username));
+                if self.runtime.system_time().micros() > reset.expires_at {
+                    panic!("Credential reset token has expired");
                 }
-                if let Some(end_time) = end_time {
-                    tournament.end_time = Some(end_time * 1_000_000); // Convert seconds to microseconds
+                if hash_reset_token(&token) != reset.token_hash {
+                    panic!("Invalid reset token");
                 }
 
+                let mut user = self
+                    .state
+                    .users
+                    .get(&username)
+                    .await
+                    .expect("Failed to get user")
+                    .unwrap_or_else(|| panic!("User '{}' not found", username));
+                self.set_credential(&mut user, &new_hash);
                 self.state
-                    .tournaments
-                    .insert(&tournament_id, tournament)
-                    .expect("Failed to update tournament");
+                    .users
+                    .insert(&username, user)
+                    .expect("Failed to update user");
+
+                // The token is single use
+                self.state
+                    .credential_resets
+                    .remove(&username)
+                    .expect("Failed to remove credential reset");
+
+                self.clear_login_sessions(&username).await;
             }
 
-            Operation::SubmitTournamentScore {
-                tournament_id,
+            Operation::ReserveUsername {
+                caller_chain_id,
                 username,
-                score,
+                reserved,
             } => {
-                // Can be submitted on any chain, but forwards to leaderboard chain for processing
+                // Only admins can edit reserved names, only on leaderboard chain
                 if !*self.state.is_leaderboard_chain.get() {
-                    // Forward to leaderboard chain
+                    panic!("Usernames can only be reserved on the leaderboard chain");
+                }
+
+                // Validate admin session
+                self.validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let username = username.to_lowercase();
+                let mut reserved_usernames = self.state.reserved_usernames.get().clone();
+                reserved_usernames.retain(|name| *name != username);
+                if reserved {
+                    reserved_usernames.push(username);
+                }
+                self.state.reserved_usernames.set(reserved_usernames);
+            }
+
+            Operation::ClearLoginLockout {
+                caller_chain_id,
+                username,
+                chain_id,
+            } => {
+                // Only admins can clear lockouts, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Login lockouts can only be cleared on the leaderboard chain");
+                }
+
+                // Validate admin session
+                self.validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                if let Some(username) = username {
+                    self.state
+                        .login_attempts
+                        .remove(&username)
+                        .expect("Failed to clear login attempts");
+                }
+                if let Some(chain_id) = chain_id {
+                    self.state
+                        .chain_login_attempts
+                        .remove(&chain_id)
+                        .expect("Failed to clear login attempts");
+                }
+            }
+
+            Operation::StartRun => {
+                // Replaces the ticket of a run that was never submitted
+                let id = *self.state.run_ticket_counter.get() + 1;
+                self.state.run_ticket_counter.set(id);
+                let ticket = RunTicket {
+                    id,
+                    issued_at: self.runtime.system_time().micros(),
+                };
+                self.state.open_run_ticket.set(Some(ticket));
+            }
+
+            Operation::SubmitPracticeScore {
+                username,
+                score,
+                tap_frames,
+                ticket_id,
+            } => {
+                // Process on player chains (any chain can submit practice scores)
+                let (submitted_at, ticket) = self.record_submission(score, ticket_id);
+                let ghost = Self::encode_tap_frames(tap_frames);
+
+                // Add run to personal history, dropping the oldest runs beyond the cap
+                self.migrate_legacy_practice_scores();
+                self.state.my_practice_history.push_back(PracticeRun {
+                    score,
+                    timestamp: submitted_at,
+                });
+                self.trim_practice_history();
+
+                // Batch the run for the leaderboard chain statistics
+                let mut pending = self
+                    .state
+                    .pending_practice_runs
+                    .get(&username)
+                    .await
+                    .expect("Failed to get pending practice runs")
+                    .unwrap_or_default();
+                pending.record(score);
+                let batch_full = pending.count >= PRACTICE_SUMMARY_BATCH_SIZE;
+                self.state
+                    .pending_practice_runs
+                    .insert(&username, pending)
+                    .expect("Failed to update pending practice runs");
+
+                // Check if this is a new personal best
+                let current_best = *self.state.my_practice_best.get();
+                let is_new_best = score > current_best;
+                if is_new_best {
+                    self.state.my_practice_best.set(score);
+
+                    // Keep the run to race against locally
+                    let recorded_at = self.runtime.system_time().micros();
+                    match self.ghost_run(score, ghost.clone(), recorded_at) {
+                        Some(ghost_run) => self
+                            .state
+                            .practice_ghosts
+                            .insert(&username, ghost_run)
+                            .expect("Failed to store ghost run"),
+                        None => self
+                            .state
+                            .practice_ghosts
+                            .remove(&username)
+                            .expect("Failed to remove ghost run"),
+                    }
+
+                    // Send message to leaderboard chain if configured
                     if let Some(leaderboard_id) = self.state.leaderboard_chain_id.get() {
-                        let message = FlappyMessage::SubmitTournamentScore {
-                            tournament_id: tournament_id.clone(),
-                            username,
+                        let message = FlappyMessage::UpdatePracticeBest {
+                            username: username.clone(),
                             score,
                             player_chain_id: self.runtime.chain_id(),
+                            ghost,
+                            submitted_at,
+                            ticket,
                         };
 
                         self.runtime
                             .prepare_message(message)
                             .send_to(*leaderboard_id);
                     }
+                }
 
-                    // Also store locally for player's personal history
-                    let mut my_scores =
-                        match self.state.my_tournament_scores.get(&tournament_id).await {
-                            Ok(Some(scores)) => scores,
-                            _ => Vec::new(),
-                        };
-                    my_scores.push(score);
-                    self.state
-                        .my_tournament_scores
-                        .insert(&tournament_id, my_scores)
-                        .expect("Failed to update personal tournament scores");
+                // Report the batch once full, or along with a new best so stats stay current
+                if batch_full || is_new_best {
+                    self.flush_practice_runs(&username).await;
                 }
             }
-        }
-    }
 
-    async fn execute_message(&mut self, message: Self::Message) {
-        // Check if message is bouncing
-        let is_bouncing = self
-            .runtime
-            .message_is_bouncing()
-            .unwrap_or_else(|| panic!("Message delivery status must be available"));
+            Operation::SetPracticeHistoryCap { cap } => {
+                if cap == 0 || cap > MAX_PRACTICE_HISTORY_CAP {
+                    panic!(
+                        "Practice history cap must be between 1 and {}",
+                        MAX_PRACTICE_HISTORY_CAP
+                    );
+                }
 
-        if is_bouncing {
-            return;
-        }
+                self.state.practice_history_cap.set(cap);
+                self.migrate_legacy_practice_scores();
+                self.trim_practice_history();
+            }
 
-        // Auto-update tournament statuses before processing messages
-        if *self.state.is_leaderboard_chain.get() {
-            self.update_tournament_statuses().await;
-        }
+            Operation::FlushPracticeRuns { username } => {
+                self.flush_practice_runs(&username).await;
+            }
 
-        match message {
-            FlappyMessage::UpdatePracticeBest {
+            // Friends operations
+            Operation::AddFriend {
                 username,
-                score,
-                player_chain_id,
+                friend_username,
             } => {
-                // Only process on leaderboard chain
-                if !*self.state.is_leaderboard_chain.get() {
-                    return;
+                if username == friend_username {
+                    panic!("Cannot add yourself as a friend");
                 }
 
-                // Create practice entry with current timestamp
-                let timestamp = self.runtime.system_time().micros();
-                let practice_entry = PracticeEntry {
-                    username: username.clone(),
-                    score,
-                    chain_id: player_chain_id,
-                    timestamp,
-                };
+                if self
+                    .state
+                    .friends
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friends")
+                {
+                    panic!("'{}' is already a friend", friend_username);
+                }
+
+                if self
+                    .state
+                    .incoming_friend_requests
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friend requests")
+                {
+                    panic!(
+                        "'{}' already sent you a friend request, accept it instead",
+                        friend_username
+                    );
+                }
 
-                // Update user's best practice score (player chain already verified it's best)
+                let request = FriendRequest {
+                    from_username: username.clone(),
+                    to_username: friend_username.clone(),
+                    requested_at: self.runtime.system_time().micros(),
+                };
                 self.state
-                    .practice_best_scores
-                    .insert(&username, practice_entry)
-                    .expect("Failed to update practice best score");
+                    .outgoing_friend_requests
+                    .insert(&friend_username, request)
+                    .expect("Failed to store friend request");
 
-                // Update global top 100 practice leaderboard
-                self.update_practice_leaderboard().await;
+                self.send_to_leaderboard_chain(FlappyMessage::FriendRequest {
+                    from_username: username,
+                    to_username: friend_username,
+                });
             }
 
-            // Tournament messages
-            FlappyMessage::SubmitTournamentScore {
-                tournament_id,
+            Operation::AcceptFriend {
                 username,
-                score,
-                player_chain_id,
+                friend_username,
             } => {
-                // Only process on leaderboard chain
-                if !*self.state.is_leaderboard_chain.get() {
-                    return;
+                if !self
+                    .state
+                    .incoming_friend_requests
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friend requests")
+                {
+                    panic!("No friend request from '{}'", friend_username);
                 }
 
-                // Process the tournament score
-                self.process_tournament_score(tournament_id, username, score, player_chain_id)
-                    .await;
+                self.state
+                    .incoming_friend_requests
+                    .remove(&friend_username)
+                    .expect("Failed to remove friend request");
+                let friend = Friend {
+                    username: friend_username.clone(),
+                    since: self.runtime.system_time().micros(),
+                };
+                self.state
+                    .friends
+                    .insert(&friend_username, friend)
+                    .expect("Failed to add friend");
+
+                self.send_to_leaderboard_chain(FlappyMessage::FriendAccepted {
+                    username,
+                    friend_username,
+                });
             }
 
-            FlappyMessage::TournamentUpdate {
-                tournament_id,
-                tournament,
+            Operation::RemoveFriend {
+                username,
+                friend_username,
             } => {
-                // Update local tournament cache on player chains
-                if !*self.state.is_leaderboard_chain.get() {
+                let mut found = false;
+                if self
+                    .state
+                    .friends
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friends")
+                {
                     self.state
-                        .tournaments
-                        .insert(&tournament_id, tournament)
-                        .expect("Failed to update tournament cache");
+                        .friends
+                        .remove(&friend_username)
+                        .expect("Failed to remove friend");
+                    found = true;
+                }
+                if self
+                    .state
+                    .incoming_friend_requests
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friend requests")
+                {
+                    self.state
+                        .incoming_friend_requests
+                        .remove(&friend_username)
+                        .expect("Failed to remove friend request");
+                    found = true;
+                }
+                if self
+                    .state
+                    .outgoing_friend_requests
+                    .contains_key(&friend_username)
+                    .await
+                    .expect("Failed to check friend requests")
+                {
+                    self.state
+                        .outgoing_friend_requests
+                        .remove(&friend_username)
+                        .expect("Failed to remove friend request");
+                    found = true;
+                }
+
+                if !found {
+                    panic!("'{}' is not a friend", friend_username);
                 }
+
+                self.send_to_leaderboard_chain(FlappyMessage::FriendRemoved {
+                    username,
+                    friend_username,
+                });
             }
 
-            FlappyMessage::RequestTournamentData {
-                tournament_id,
-                requester_chain_id,
+            // Challenge operations
+            Operation::ChallengePlayer {
+                username,
+                opponent,
+                seed,
+                deadline,
             } => {
-                // Only process on leaderboard chain
-                if !*self.state.is_leaderboard_chain.get() {
-                    return;
+                if username == opponent {
+                    panic!("Cannot challenge yourself");
                 }
 
-                if let Ok(Some(tournament)) = self.state.tournaments.get(&tournament_id).await {
-                    let response = FlappyMessage::TournamentUpdate {
-                        tournament_id,
-                        tournament,
-                    };
-
-                    self.runtime
-                        .prepare_message(response)
-                        .send_to(requester_chain_id);
+                let deadline = deadline.saturating_mul(1_000_000); // Convert seconds to microseconds
+                if deadline <= self.runtime.system_time().micros() {
+                    panic!("Challenge deadline must be in the future");
                 }
+
+                self.send_to_leaderboard_chain(FlappyMessage::CreateChallenge {
+                    challenger: username,
+                    opponent,
+                    seed,
+                    deadline,
+                });
             }
-        }
-    }
 
-    async fn store(mut self) {
-        self.state.save().await.expect("Failed to save state");
-    }
-}
+            Operation::AcceptChallenge {
+                username,
+                challenge_id,
+            } => {
+                let challenge = self.my_challenge(&challenge_id).await;
+                if challenge.opponent != username {
+                    panic!("Only the challenged player can accept");
+                }
+                if challenge.status != ChallengeStatus::Pending {
+                    panic!("Challenge is not pending");
+                }
 
-impl FlappyContract {
-    async fn update_practice_leaderboard(&mut self) {
-        // Collect all practice scores
-        let mut all_practice_scores = Vec::new();
+                self.send_to_leaderboard_chain(FlappyMessage::AcceptChallenge {
+                    username,
+                    challenge_id,
+                });
+            }
 
-        // Get all usernames who have practice scores
-        let usernames = self
-            .state
-            .practice_best_scores
+            Operation::SubmitChallengeScore {
+                username,
+                challenge_id,
+                score,
+            } => {
+                let challenge = self.my_challenge(&challenge_id).await;
+                if challenge.status != ChallengeStatus::Accepted {
+                    panic!("Challenge is not accepted");
+                }
+                let existing_score = if challenge.challenger == username {
+                    challenge.challenger_score
+                } else if challenge.opponent == username {
+                    challenge.opponent_score
+                } else {
+                    panic!("User '{}' is not part of this challenge", username);
+                };
+                if existing_score.is_some() {
+                    panic!("Score already submitted for this challenge");
+                }
+                if self.runtime.system_time().micros() > challenge.deadline {
+                    panic!("Challenge deadline has passed");
+                }
+
+                self.send_to_leaderboard_chain(FlappyMessage::SubmitChallengeScore {
+                    username,
+                    challenge_id,
+                    score,
+                });
+            }
+
+            Operation::ResolveChallenge {
+                username,
+                challenge_id,
+            } => {
+                if !*self.state.is_leaderboard_chain.get() {
+                    // Players settle their own challenges through the leaderboard chain
+                    let challenge = self.my_challenge(&challenge_id).await;
+                    if challenge.challenger != username && challenge.opponent != username {
+                        panic!("User '{}' is not part of this challenge", username);
+                    }
+                    if self.runtime.system_time().micros() <= challenge.deadline {
+                        panic!("Challenge cannot be resolved before its deadline");
+                    }
+
+                    self.send_to_leaderboard_chain(FlappyMessage::ResolveChallenge {
+                        username,
+                        challenge_id,
+                    });
+                } else {
+                    if self.admin_signer().is_none() {
+                        panic!("Only an admin key can resolve any challenge");
+                    }
+
+                    let mut challenge = match self.state.challenges.get(&challenge_id).await {
+                        Ok(Some(challenge)) => challenge,
+                        _ => panic!("Challenge not found"),
+                    };
+
+                    if !self.settle_expired_challenge(&mut challenge).await {
+                        panic!("Challenge cannot be resolved before its deadline");
+                    }
+                    self.save_challenge(challenge).await;
+                }
+            }
+
+            // Tournament management operations
+            Operation::CreateTournament {
+                caller_chain_id,
+                name,
+                description,
+                start_time,
+                end_time,
+            } => {
+                // Only admins can create tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be created on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                // Generate unique tournament ID
+                let counter = *self.state.tournament_counter.get();
+                let tournament_id = format!("tournament_{}", counter);
+                self.state.tournament_counter.set(counter + 1);
+
+                // Validate time constraints (comparing raw seconds before conversion)
+                if let (Some(start), Some(end)) = (start_time, end_time) {
+                    if end <= start {
+                        panic!("End time must be after start time");
+                    }
+                }
+
+                // Create tournament - convert user-provided timestamps from seconds to microseconds
+                let tournament = Tournament {
+                    id: tournament_id.clone(),
+                    name,
+                    description,
+                    creator: admin_user.username.clone(), // Use authenticated admin's username
+                    status: TournamentStatus::Registration,
+                    start_time: start_time.map(|t| t * 1_000_000), // Convert seconds to microseconds
+                    end_time: end_time.map(|t| t * 1_000_000), // Convert seconds to microseconds
+                    participants: Vec::new(),
+                    results: Vec::new(),
+                    created_at: self.runtime.system_time().micros(),
+                    is_pinned: false,
+                    pinned_at: None,
+                    pinned_by: None,
+                };
+
+                self.save_tournament(tournament);
+            }
+
+            Operation::JoinTournament {
+                tournament_id,
+                username,
+            } => {
+                // Tournament joining can happen on any chain, but data is stored on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournament joining must be done on the leaderboard chain");
+                }
+
+                // Get tournament and validate
+                let mut tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                if tournament.status == TournamentStatus::Ended {
+                    panic!("Cannot join tournament that has already ended");
+                }
+
+                if tournament.participants.contains(&username) {
+                    panic!("User already joined this tournament");
+                }
+
+                if self.active_restriction(&username).await.is_some() {
+                    panic!("User '{}' is banned or suspended", username);
+                }
+
+                // Until schema version 4 the entry count migration would count this join again
+                if *self.state.schema_version.get() < 4 {
+                    panic!("Tournament entries are still being counted, try again shortly");
+                }
+
+                // Add user to tournament
+                tournament.participants.push(username.clone());
+                self.save_tournament(tournament);
+
+                self.record_tournament_entry(&username).await;
+            }
+
+            Operation::StartTournament {
+                caller_chain_id,
+                tournament_id,
+            } => {
+                // Only admins can start tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be started on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let _admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let mut tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                if tournament.status != TournamentStatus::Registration {
+                    panic!("Tournament is not in registration phase");
+                }
+
+                tournament.status = TournamentStatus::Active;
+                if tournament.start_time.is_none() {
+                    tournament.start_time = Some(self.runtime.system_time().micros());
+                }
+
+                self.save_tournament(tournament);
+
+                self.emit_event(FlappyEvent::TournamentStatusChanged {
+                    tournament_id,
+                    status: TournamentStatus::Active,
+                });
+            }
+
+            Operation::EndTournament {
+                caller_chain_id,
+                tournament_id,
+                results,
+            } => {
+                // Only admins can end tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be ended on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let _admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let mut tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                if tournament.status != TournamentStatus::Active {
+                    panic!("Tournament is not active");
+                }
+
+                tournament.status = TournamentStatus::Ended;
+                tournament.end_time = Some(self.runtime.system_time().micros());
+                let hidden_usernames = self.hidden_score_usernames().await;
+                tournament.results = results;
+                tournament
+                    .results
+                    .retain(|r| !hidden_usernames.contains(&r.username));
+                rank_tournament_results(&mut tournament.results);
+
+                self.save_tournament(tournament.clone());
+
+                self.emit_event(FlappyEvent::TournamentStatusChanged {
+                    tournament_id,
+                    status: TournamentStatus::Ended,
+                });
+                self.finalize_tournament_achievements(&tournament).await;
+            }
+
+            Operation::PinTournament {
+                caller_chain_id,
+                tournament_id,
+                pin,
+            } => {
+                // Only admins can pin tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be pinned on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let mut tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                tournament.is_pinned = pin;
+                if pin {
+                    tournament.pinned_at = Some(self.runtime.system_time().micros());
+                    tournament.pinned_by = Some(admin_user.username.clone()); // Use authenticated admin's username
+
+                    // Add to pinned list if not already there
+                    let mut pinned = self.state.pinned_tournaments.get().clone();
+                    if !pinned.contains(&tournament_id) {
+                        pinned.push(tournament_id.clone());
+                        self.state.pinned_tournaments.set(pinned);
+                    }
+                } else {
+                    tournament.pinned_at = None;
+                    tournament.pinned_by = None;
+
+                    // Remove from pinned list
+                    let mut pinned = self.state.pinned_tournaments.get().clone();
+                    pinned.retain(|id| id != &tournament_id);
+                    self.state.pinned_tournaments.set(pinned);
+                }
+
+                self.save_tournament(tournament);
+            }
+
+            Operation::DeleteTournament {
+                caller_chain_id,
+                tournament_id,
+            } => {
+                // Only admins can delete tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be deleted on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let _admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let _tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                // Remove from tournaments map
+                self.state
+                    .tournaments
+                    .remove(&tournament_id)
+                    .expect("Failed to delete tournament");
+
+                // Remove from pinned list if present
+                let mut pinned = self.state.pinned_tournaments.get().clone();
+                pinned.retain(|id| id != &tournament_id);
+                self.state.pinned_tournaments.set(pinned);
+
+                self.emit_event(FlappyEvent::TournamentDeleted { tournament_id });
+            }
+
+            Operation::UpdateTournament {
+                caller_chain_id,
+                tournament_id,
+                name,
+                description,
+                start_time,
+                end_time,
+            } => {
+                // Only admins can update tournaments, only on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Tournaments can only be updated on the leaderboard chain");
+                }
+
+                // Validate admin session
+                let _admin_user = self
+                    .validate_admin_session(caller_chain_id)
+                    .await
+                    .unwrap_or_else(|msg| panic!("{}", msg));
+
+                let mut tournament = self
+                    .state
+                    .tournaments
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament")
+                    .expect("Tournament not found");
+
+                // Validate time constraints if both provided (comparing raw seconds before conversion)
+                if let (Some(start), Some(end)) = (start_time, end_time) {
+                    if end <= start {
+                        panic!("End time must be after start time");
+                    }
+                }
+
+                // Update fields - convert user-provided timestamps from seconds to microseconds
+                if let Some(name) = name {
+                    tournament.name = name;
+                }
+                if let Some(description) = description {
+                    tournament.description = description;
+                }
+                if let Some(start_time) = start_time {
+                    tournament.start_time = Some(start_time * 1_000_000); // Convert seconds to microseconds
+                }
+                if let Some(end_time) = end_time {
+                    tournament.end_time = Some(end_time * 1_000_000); // Convert seconds to microseconds
+                }
+
+                self.save_tournament(tournament);
+            }
+
+            Operation::MoveLeaderboardChain {
+                new_leaderboard_chain_id,
+            } => {
+                if !*self.state.is_leaderboard_chain.get() {
+                    panic!("Only the leaderboard chain can be moved");
+                }
+
+                // Admin sessions are looked up by a chain ID anyone can name, so moving the whole
+                // leaderboard takes a block signed by an admin key
+                let Some(admin) = self.admin_signer() else {
+                    panic!("Only an admin key can move the leaderboard chain");
+                };
+
+                if new_leaderboard_chain_id == self.runtime.chain_id() {
+                    panic!("This chain is already the leaderboard chain");
+                }
+
+                match self.state.leaderboard_move.get() {
+                    None => {
+                        self.state.leaderboard_move.set(Some(LeaderboardMove {
+                            new_chain_id: new_leaderboard_chain_id,
+                            step: LeaderboardMoveStep::Users,
+                            cursor: None,
+                            started_by: admin.to_string(),
+                            started_at: self.runtime.system_time().micros(),
+                        }));
+                    }
+                    Some(current) if current.new_chain_id == new_leaderboard_chain_id => {}
+                    Some(_) => panic!("Leaderboard chain is already moving to another chain"),
+                }
+
+                self.advance_leaderboard_move().await;
+            }
+
+            Operation::CancelLeaderboardMove => {
+                if self.admin_signer().is_none() {
+                    panic!("Only an admin key can cancel the leaderboard chain move");
+                }
+                if !self.leaderboard_move_in_progress() {
+                    panic!("No leaderboard chain move to cancel");
+                }
+                self.cancel_leaderboard_move().await;
+            }
+
+            Operation::SubmitTournamentScore {
+                tournament_id,
+                username,
+                score,
+                tap_frames,
+                ticket_id,
+            } => {
+                // Can be submitted on any chain, but forwards to leaderboard chain for processing
+                if !*self.state.is_leaderboard_chain.get() {
+                    let (submitted_at, ticket) = self.record_submission(score, ticket_id);
+
+                    // Forward to leaderboard chain
+                    if let Some(leaderboard_id) = self.state.leaderboard_chain_id.get() {
+                        let message = FlappyMessage::SubmitTournamentScore {
+                            tournament_id: tournament_id.clone(),
+                            username,
+                            score,
+                            player_chain_id: self.runtime.chain_id(),
+                            ghost: Self::encode_tap_frames(tap_frames),
+                            submitted_at,
+                            ticket,
+                        };
+
+                        self.runtime
+                            .prepare_message(message)
+                            .send_to(*leaderboard_id);
+                    }
+
+                    // Also store locally for player's personal history
+                    let mut my_scores =
+                        match self.state.my_tournament_scores.get(&tournament_id).await {
+                            Ok(Some(scores)) => scores,
+                            _ => Vec::new(),
+                        };
+                    my_scores.push(score);
+                    self.state
+                        .my_tournament_scores
+                        .insert(&tournament_id, my_scores)
+                        .expect("Failed to update personal tournament scores");
+                }
+            }
+        }
+    }
+
+    async fn execute_message(&mut self, message: Self::Message) {
+        // Check if message is bouncing
+        let is_bouncing = self
+            .runtime
+            .message_is_bouncing()
+            .unwrap_or_else(|| panic!("Message delivery status must be available"));
+
+        if is_bouncing {
+            return;
+        }
+
+        // Messages a former leaderboard chain passed on are handled as sent from their origin
+        self.forwarded_origin = None;
+        let message = match message {
+            FlappyMessage::Forwarded {
+                origin_chain_id,
+                message,
+            } => {
+                if self.runtime.message_origin_chain_id()
+                    != *self.state.previous_leaderboard_chain_id.get()
+                {
+                    return;
+                }
+                self.forwarded_origin = Some(origin_chain_id);
+                *message
+            }
+            message => message,
+        };
+
+        // Messages for a leaderboard chain that is moving away belong to the new chain
+        if self.redirect_moved_message(&message) {
+            return;
+        }
+
+        self.configure_from_parameters();
+        self.migrate_state().await;
+
+        // Auto-update tournament statuses before processing messages
+        if *self.state.is_leaderboard_chain.get() {
+            self.update_tournament_statuses().await;
+        }
+
+        self.handle_message(message).await;
+    }
+
+    async fn process_streams(&mut self, updates: Vec<StreamUpdate>) {
+        let stream_name = StreamName::from(FLAPPY_STREAM_NAME);
+        for update in updates {
+            if Some(update.chain_id) != *self.state.leaderboard_chain_id.get()
+                || update.stream_id.stream_name != stream_name
+            {
+                continue;
+            }
+            for index in update.new_indices() {
+                // Events after a move are the old chain's, no longer followed
+                if Some(update.chain_id) != *self.state.leaderboard_chain_id.get() {
+                    break;
+                }
+                let event = self
+                    .runtime
+                    .read_event(update.chain_id, stream_name.clone(), index);
+                self.apply_leaderboard_event(event).await;
+            }
+        }
+    }
+
+    async fn store(mut self) {
+        self.state.save().await.expect("Failed to save state");
+    }
+}
+
+impl FlappyContract {
+    // Applies a message; also replays the messages held by a cancelled leaderboard chain move
+    async fn handle_message(&mut self, message: FlappyMessage) {
+        match message {
+            FlappyMessage::UpdatePracticeBest {
+                username,
+                score,
+                player_chain_id,
+                ghost,
+                submitted_at,
+                ticket,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Drop submissions arriving faster than the game can be played
+                let submission_gap = self.submission_gap(player_chain_id, submitted_at).await;
+                if !self
+                    .accept_chain_submission(player_chain_id, score, submitted_at, &ticket)
+                    .await
+                {
+                    return;
+                }
+
+                // Drop scores from banned, suspended and deleted accounts
+                if self.active_restriction(&username).await.is_some() {
+                    return;
+                }
+
+                // Hold suspicious scores back until an admin reviews them
+                if self
+                    .moderate_score(
+                        &username,
+                        score,
+                        None,
+                        player_chain_id,
+                        &ghost,
+                        submission_gap,
+                    )
+                    .await
+                {
+                    return;
+                }
+
+                // Player chain already verified it's their best
+                self.apply_practice_best(username, score, player_chain_id, ghost)
+                    .await;
+            }
+
+            FlappyMessage::PracticeRunsSummary {
+                username,
+                summary,
+                player_chain_id: _,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Drop malformed histograms along with the rest of the summary
+                if !summary.has_valid_buckets()
+                    || self.active_restriction(&username).await.is_some()
+                {
+                    return;
+                }
+
+                self.record_practice_runs(&username, &summary).await;
+
+                if !summary.is_empty() {
+                    self.unlock_achievement(&username, AchievementKind::FirstRun, None)
+                        .await;
+                }
+            }
+
+            // Friends messages
+            message @ (FlappyMessage::FriendRequest { .. }
+            | FlappyMessage::FriendAccepted { .. }
+            | FlappyMessage::FriendRemoved { .. }) => {
+                if *self.state.is_leaderboard_chain.get() {
+                    self.route_friend_message(message).await;
+                } else if self.message_origin_chain_id() == *self.state.leaderboard_chain_id.get() {
+                    self.apply_friend_message(message).await;
+                }
+            }
+
+            // Challenge messages
+            FlappyMessage::CreateChallenge {
+                challenger,
+                opponent,
+                seed,
+                deadline,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Both players must be registered, and the challenger sends from their own chain
+                if !self.is_from_user_chain(&challenger).await
+                    || self.user_chain_id(&opponent).await.is_none()
+                {
+                    return;
+                }
+
+                // Generate unique challenge ID
+                let counter = *self.state.challenge_counter.get();
+                let challenge_id = format!("challenge_{}", counter);
+                self.state.challenge_counter.set(counter + 1);
+
+                let challenge = Challenge {
+                    id: challenge_id,
+                    challenger,
+                    opponent,
+                    seed,
+                    deadline,
+                    status: ChallengeStatus::Pending,
+                    challenger_score: None,
+                    opponent_score: None,
+                    winner: None,
+                    created_at: self.runtime.system_time().micros(),
+                    completed_at: None,
+                };
+                self.save_challenge(challenge).await;
+            }
+
+            FlappyMessage::AcceptChallenge {
+                username,
+                challenge_id,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Only the opponent's own chain may accept for them
+                if !self.is_from_user_chain(&username).await {
+                    return;
+                }
+
+                let mut challenge = match self.state.challenges.get(&challenge_id).await {
+                    Ok(Some(challenge)) => challenge,
+                    _ => return, // Challenge not found
+                };
+
+                if !self.settle_expired_challenge(&mut challenge).await {
+                    if challenge.status != ChallengeStatus::Pending
+                        || challenge.opponent != username
+                    {
+                        return;
+                    }
+                    challenge.status = ChallengeStatus::Accepted;
+                }
+                self.save_challenge(challenge).await;
+            }
+
+            FlappyMessage::SubmitChallengeScore {
+                username,
+                challenge_id,
+                score,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Players submit only their own runs, from their own chains
+                if !self.is_from_user_chain(&username).await {
+                    return;
+                }
+
+                let mut challenge = match self.state.challenges.get(&challenge_id).await {
+                    Ok(Some(challenge)) => challenge,
+                    _ => return, // Challenge not found
+                };
+
+                if !self.settle_expired_challenge(&mut challenge).await {
+                    if challenge.status != ChallengeStatus::Accepted {
+                        return;
+                    }
+
+                    // One run per player
+                    let slot = if challenge.challenger == username {
+                        &mut challenge.challenger_score
+                    } else if challenge.opponent == username {
+                        &mut challenge.opponent_score
+                    } else {
+                        return;
+                    };
+                    if slot.is_some() {
+                        return;
+                    }
+                    *slot = Some(score);
+
+                    if challenge.challenger_score.is_some() && challenge.opponent_score.is_some() {
+                        self.complete_challenge(&mut challenge).await;
+                    }
+                }
+                self.save_challenge(challenge).await;
+            }
+
+            FlappyMessage::LoginOrRegister { username, hash } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                let origin_chain_id = self
+                    .message_origin_chain_id()
+                    .expect("Message origin must be available");
+                self.login_or_register(username, hash, origin_chain_id)
+                    .await;
+
+                // Let the chain know who is logged in on it
+                let result = self
+                    .state
+                    .login_results
+                    .get(&origin_chain_id)
+                    .await
+                    .expect("Failed to get login result")
+                    .expect("Login result must be stored");
+                self.runtime
+                    .prepare_message(FlappyMessage::LoginCompleted { result })
+                    .send_to(origin_chain_id);
+            }
+
+            FlappyMessage::LoginCompleted { result } => {
+                if self.message_origin_chain_id() != *self.state.leaderboard_chain_id.get() {
+                    return;
+                }
+                if result.success {
+                    self.state.current_user.set(result.user.clone());
+                }
+                // Kept so the chain's own service can report how its login went
+                let chain_id = self.runtime.chain_id();
+                self.state
+                    .login_results
+                    .insert(&chain_id, result)
+                    .expect("Failed to store login result");
+            }
+
+            FlappyMessage::ResolveChallenge {
+                username,
+                challenge_id,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Only the parties may settle a challenge, from their own chains
+                if !self.is_from_user_chain(&username).await {
+                    return;
+                }
+                let mut challenge = match self.state.challenges.get(&challenge_id).await {
+                    Ok(Some(challenge)) => challenge,
+                    _ => return, // Challenge not found
+                };
+                if challenge.challenger != username && challenge.opponent != username {
+                    return;
+                }
+
+                if self.settle_expired_challenge(&mut challenge).await {
+                    self.save_challenge(challenge).await;
+                }
+            }
+
+            FlappyMessage::ChallengeUpdate { challenge } => {
+                // Update local challenge cache on player chains, from the leaderboard chain only
+                if self.message_origin_chain_id() != *self.state.leaderboard_chain_id.get() {
+                    return;
+                }
+                let challenge_id = challenge.id.clone();
+                self.state
+                    .my_challenges
+                    .insert(&challenge_id, challenge)
+                    .expect("Failed to update challenge cache");
+            }
+
+            // Tournament messages
+            FlappyMessage::SubmitTournamentScore {
+                tournament_id,
+                username,
+                score,
+                player_chain_id,
+                ghost,
+                submitted_at,
+                ticket,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                // Drop submissions arriving faster than the game can be played
+                let submission_gap = self.submission_gap(player_chain_id, submitted_at).await;
+                if !self
+                    .accept_chain_submission(player_chain_id, score, submitted_at, &ticket)
+                    .await
+                {
+                    return;
+                }
+
+                // Process the tournament score
+                self.process_tournament_score(
+                    tournament_id,
+                    username,
+                    score,
+                    player_chain_id,
+                    ghost,
+                    submission_gap,
+                )
+                .await;
+            }
+
+            FlappyMessage::TournamentUpdate {
+                tournament_id,
+                tournament,
+            } => {
+                // Update local tournament cache on player chains
+                if !*self.state.is_leaderboard_chain.get() {
+                    self.state
+                        .tournaments
+                        .insert(&tournament_id, tournament)
+                        .expect("Failed to update tournament cache");
+                }
+            }
+
+            FlappyMessage::RequestTournamentData {
+                tournament_id,
+                requester_chain_id,
+            } => {
+                // Only process on leaderboard chain
+                if !*self.state.is_leaderboard_chain.get() {
+                    return;
+                }
+
+                if let Ok(Some(tournament)) = self.state.tournaments.get(&tournament_id).await {
+                    let response = FlappyMessage::TournamentUpdate {
+                        tournament_id,
+                        tournament,
+                    };
+
+                    self.runtime
+                        .prepare_message(response)
+                        .send_to(requester_chain_id);
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardUsers { users } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for snapshot in users {
+                    self.restore_user(snapshot);
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardTournaments { tournaments } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for snapshot in tournaments {
+                    self.restore_tournament(snapshot);
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardChallenges { challenges } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for challenge in challenges {
+                    self.state
+                        .challenges
+                        .insert(&challenge.id, challenge.clone())
+                        .expect("Failed to restore challenge");
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardHeadToHead { records } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for (pair, record) in records {
+                    self.state
+                        .head_to_head
+                        .insert(&pair, record)
+                        .expect("Failed to restore head-to-head record");
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardPendingScores { pending_scores } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for pending in pending_scores {
+                    self.state
+                        .pending_scores
+                        .insert(&pending.id, pending.clone())
+                        .expect("Failed to restore pending score");
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardLoginAttempts { chain_attempts } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                for (chain_id, attempts) in chain_attempts {
+                    self.state
+                        .chain_login_attempts
+                        .insert(&chain_id, attempts)
+                        .expect("Failed to restore chain login attempts");
+                }
+            }
+
+            FlappyMessage::MoveLeaderboardBoards { boards } => {
+                if !self.accept_leaderboard_move() {
+                    return;
+                }
+                let Some(old_chain_id) = *self.state.leaderboard_chain_id.get() else {
+                    return;
+                };
+                self.restore_boards(boards);
+
+                // Take over and let the old chain retire, taking the messages it passes on
+                let chain_id = self.runtime.chain_id();
+                self.follow_leaderboard_chain(chain_id);
+                self.state
+                    .previous_leaderboard_chain_id
+                    .set(Some(old_chain_id));
+                self.runtime
+                    .prepare_message(FlappyMessage::LeaderboardMoveAccepted)
+                    .send_to(old_chain_id);
+            }
+
+            FlappyMessage::LeaderboardMoveAccepted => {
+                let Some(leaderboard_move) = self.state.leaderboard_move.get().clone() else {
+                    return;
+                };
+                if leaderboard_move.step != LeaderboardMoveStep::AwaitingNewChain
+                    || self.runtime.message_origin_chain_id() != Some(leaderboard_move.new_chain_id)
+                {
+                    return;
+                }
+                self.retire_leaderboard_chain(leaderboard_move).await;
+            }
+
+            FlappyMessage::LeaderboardMoveRejected => {
+                let Some(leaderboard_move) = self.state.leaderboard_move.get().clone() else {
+                    return;
+                };
+                if leaderboard_move.step == LeaderboardMoveStep::Completed
+                    || self.runtime.message_origin_chain_id() != Some(leaderboard_move.new_chain_id)
+                {
+                    return;
+                }
+                self.cancel_leaderboard_move().await;
+            }
+
+            // Unwrapped in execute_message before it gets here
+            FlappyMessage::Forwarded { .. } => {}
+        }
+    }
+
+    // Whether the message being handled came from the chain `username` registered from
+    async fn is_from_user_chain(&mut self, username: &str) -> bool {
+        let origin_chain_id = self.message_origin_chain_id();
+        origin_chain_id.is_some() && origin_chain_id == self.user_chain_id(username).await
+    }
+
+    // Chain the message being handled came from, looking through forwarding by a former
+    // leaderboard chain
+    fn message_origin_chain_id(&mut self) -> Option<ChainId> {
+        self.forwarded_origin
+            .or_else(|| self.runtime.message_origin_chain_id())
+    }
+
+    async fn update_practice_leaderboard(&mut self) {
+        // Collect all practice scores
+        let mut all_practice_scores = Vec::new();
+
+        // Get all usernames who have practice scores
+        let usernames = self
+            .state
+            .practice_best_scores
+            .indices()
+            .await
+            .expect("Failed to get practice score usernames");
+        let hidden_usernames = self.hidden_score_usernames().await;
+
+        for username in usernames {
+            if hidden_usernames.contains(&username) {
+                continue;
+            }
+            if let Ok(Some(entry)) = self.state.practice_best_scores.get(&username).await {
+                all_practice_scores.push(entry);
+            }
+        }
+
+        // Sort by score descending (highest first)
+        all_practice_scores.sort_by(|a, b| b.score.cmp(&a.score));
+
+        // Take top 100 and update the leaderboard
+        let top_100: Vec<PracticeEntry> = all_practice_scores.into_iter().take(100).collect();
+
+        self.state.practice_leaderboard.set(top_100);
+    }
+
+    fn practice_history_cap(&self) -> usize {
+        match *self.state.practice_history_cap.get() {
+            0 => DEFAULT_PRACTICE_HISTORY_CAP as usize,
+            cap => cap as usize,
+        }
+    }
+
+    fn migrate_legacy_practice_scores(&mut self) {
+        // Scores recorded before the bounded history existed have no timestamp
+        let legacy_scores = self.state.my_practice_scores.get().clone();
+        if legacy_scores.is_empty() {
+            return;
+        }
+
+        let keep_from = legacy_scores
+            .len()
+            .saturating_sub(self.practice_history_cap());
+        for score in &legacy_scores[keep_from..] {
+            self.state.my_practice_history.push_back(PracticeRun {
+                score: *score,
+                timestamp: 0,
+            });
+        }
+        self.state.my_practice_scores.set(Vec::new());
+    }
+
+    fn trim_practice_history(&mut self) {
+        let cap = self.practice_history_cap();
+        while self.state.my_practice_history.count() > cap {
+            self.state.my_practice_history.delete_front();
+        }
+    }
+
+    async fn flush_practice_runs(&mut self, username: &str) {
+        // Nothing to report until the game is configured
+        let Some(leaderboard_id) = *self.state.leaderboard_chain_id.get() else {
+            return;
+        };
+
+        let summary = match self.state.pending_practice_runs.get(username).await {
+            Ok(Some(summary)) if !summary.is_empty() => summary,
+            _ => return,
+        };
+
+        self.state
+            .pending_practice_runs
+            .remove(username)
+            .expect("Failed to clear pending practice runs");
+
+        let message = FlappyMessage::PracticeRunsSummary {
+            username: username.to_string(),
+            summary,
+            player_chain_id: self.runtime.chain_id(),
+        };
+
+        self.runtime
+            .prepare_message(message)
+            .send_to(leaderboard_id);
+    }
+
+    async fn record_practice_runs(&mut self, username: &str, summary: &PracticeRunsSummary) {
+        let timestamp = self.runtime.system_time().micros();
+
+        // Update the player's own statistics
+        let mut stats = self
+            .state
+            .practice_stats
+            .get(username)
+            .await
+            .expect("Failed to get practice stats")
+            .unwrap_or_default();
+        let is_new_player = stats.total_runs == 0;
+        stats.add_runs(summary, timestamp);
+        self.state
+            .practice_stats
+            .insert(username, stats)
+            .expect("Failed to update practice stats");
+
+        // Update the global statistics
+        let mut global_stats = self.state.global_practice_stats.get().clone();
+        global_stats.add_runs(summary, timestamp);
+        if is_new_player && !summary.is_empty() {
+            global_stats.players += 1;
+        }
+        self.state.global_practice_stats.set(global_stats);
+    }
+
+    fn send_to_leaderboard_chain(&mut self, message: FlappyMessage) {
+        let Some(leaderboard_id) = *self.state.leaderboard_chain_id.get() else {
+            panic!("Leaderboard chain not configured");
+        };
+
+        self.runtime
+            .prepare_message(message)
+            .send_to(leaderboard_id);
+    }
+
+    async fn user_chain_id(&self, username: &str) -> Option<ChainId> {
+        match self.state.users.get(username).await {
+            Ok(Some(user)) => user.chain_id,
+            _ => None,
+        }
+    }
+
+    async fn route_friend_message(&mut self, message: FlappyMessage) {
+        // Only the chain a user registered from may act for them
+        let sender = match &message {
+            FlappyMessage::FriendRequest { from_username, .. } => from_username.clone(),
+            FlappyMessage::FriendAccepted { username, .. }
+            | FlappyMessage::FriendRemoved { username, .. } => username.clone(),
+            _ => return,
+        };
+        if !self.is_from_user_chain(&sender).await {
+            return;
+        }
+
+        match message {
+            FlappyMessage::FriendRequest {
+                from_username,
+                to_username,
+            } => match self.user_chain_id(&to_username).await {
+                Some(chain_id) if from_username != to_username => {
+                    let request = FlappyMessage::FriendRequest {
+                        from_username,
+                        to_username,
+                    };
+                    self.deliver_friend_message(chain_id, request).await;
+                }
+                _ => {
+                    // Unknown user: withdraw the request on the sender's chain
+                    let origin_chain_id = self
+                        .message_origin_chain_id()
+                        .expect("Message origin must be available");
+                    let withdrawal = FlappyMessage::FriendRemoved {
+                        username: to_username,
+                        friend_username: from_username,
+                    };
+                    self.deliver_friend_message(origin_chain_id, withdrawal)
+                        .await;
+                }
+            },
+
+            FlappyMessage::FriendAccepted {
+                username,
+                friend_username,
+            } => {
+                self.update_friendship(&username, &friend_username, true)
+                    .await;
+                self.update_friendship(&friend_username, &username, true)
+                    .await;
+
+                if let Some(chain_id) = self.user_chain_id(&friend_username).await {
+                    let accepted = FlappyMessage::FriendAccepted {
+                        username,
+                        friend_username,
+                    };
+                    self.deliver_friend_message(chain_id, accepted).await;
+                }
+            }
+
+            FlappyMessage::FriendRemoved {
+                username,
+                friend_username,
+            } => {
+                self.update_friendship(&username, &friend_username, false)
+                    .await;
+                self.update_friendship(&friend_username, &username, false)
+                    .await;
+
+                if let Some(chain_id) = self.user_chain_id(&friend_username).await {
+                    let removed = FlappyMessage::FriendRemoved {
+                        username,
+                        friend_username,
+                    };
+                    self.deliver_friend_message(chain_id, removed).await;
+                }
+            }
+
+            _ => {}
+        }
+    }
+
+    async fn deliver_friend_message(&mut self, chain_id: ChainId, message: FlappyMessage) {
+        // Users registered from the leaderboard chain itself are handled in place
+        if chain_id == self.runtime.chain_id() {
+            self.apply_friend_message(message).await;
+        } else {
+            self.runtime.prepare_message(message).send_to(chain_id);
+        }
+    }
+
+    async fn apply_friend_message(&mut self, message: FlappyMessage) {
+        let timestamp = self.runtime.system_time().micros();
+
+        match message {
+            FlappyMessage::FriendRequest {
+                from_username,
+                to_username,
+            } => {
+                let already_friends = self
+                    .state
+                    .friends
+                    .contains_key(&from_username)
+                    .await
+                    .expect("Failed to check friends");
+                if already_friends {
+                    return;
+                }
+
+                let request = FriendRequest {
+                    from_username: from_username.clone(),
+                    to_username,
+                    requested_at: timestamp,
+                };
+                self.state
+                    .incoming_friend_requests
+                    .insert(&from_username, request)
+                    .expect("Failed to store friend request");
+            }
+
+            FlappyMessage::FriendAccepted { username, .. } => {
+                self.state
+                    .outgoing_friend_requests
+                    .remove(&username)
+                    .expect("Failed to remove friend request");
+                let friend = Friend {
+                    username: username.clone(),
+                    since: timestamp,
+                };
+                self.state
+                    .friends
+                    .insert(&username, friend)
+                    .expect("Failed to add friend");
+            }
+
+            FlappyMessage::FriendRemoved { username, .. } => {
+                self.state
+                    .friends
+                    .remove(&username)
+                    .expect("Failed to remove friend");
+                self.state
+                    .incoming_friend_requests
+                    .remove(&username)
+                    .expect("Failed to remove friend request");
+                self.state
+                    .outgoing_friend_requests
+                    .remove(&username)
+                    .expect("Failed to remove friend request");
+            }
+
+            _ => {}
+        }
+    }
+
+    async fn update_friendship(&mut self, username: &str, friend_username: &str, add: bool) {
+        let mut friends = self
+            .state
+            .friendships
+            .get(username)
+            .await
+            .expect("Failed to get friendships")
+            .unwrap_or_default();
+
+        friends.retain(|friend| friend != friend_username);
+        if add {
+            friends.push(friend_username.to_string());
+        }
+
+        if friends.is_empty() {
+            self.state
+                .friendships
+                .remove(username)
+                .expect("Failed to update friendships");
+        } else {
+            self.state
+                .friendships
+                .insert(username, friends)
+                .expect("Failed to update friendships");
+        }
+    }
+
+    fn encode_tap_frames(tap_frames: Option<Vec<u32>>) -> Option<Vec<u8>> {
+        tap_frames.map(|frames| {
+            encode_ghost_frames(&frames)
+                .unwrap_or_else(|error| panic!("Invalid ghost run: {}", error))
+        })
+    }
+
+    // Validates an encoded ghost run received from another chain
+    fn ghost_run(&self, score: u64, ghost: Option<Vec<u8>>, recorded_at: u64) -> Option<GhostRun> {
+        let data = ghost.filter(|data| data.len() <= MAX_GHOST_RUN_BYTES)?;
+        let frames = decode_ghost_frames(&data).ok()?;
+
+        Some(GhostRun {
+            score,
+            data,
+            tap_count: frames.len() as u32,
+            recorded_at,
+        })
+    }
+
+    async fn my_challenge(&self, challenge_id: &str) -> Challenge {
+        match self.state.my_challenges.get(challenge_id).await {
+            Ok(Some(challenge)) => challenge,
+            _ => panic!("Challenge '{}' not found", challenge_id),
+        }
+    }
+
+    // Settles a challenge whose deadline has passed, returning whether it did
+    async fn settle_expired_challenge(&mut self, challenge: &mut Challenge) -> bool {
+        if self.runtime.system_time().micros() <= challenge.deadline {
+            return false;
+        }
+
+        match challenge.status {
+            ChallengeStatus::Pending => {
+                challenge.status = ChallengeStatus::Expired;
+                challenge.completed_at = Some(self.runtime.system_time().micros());
+                true
+            }
+            ChallengeStatus::Accepted
+                if challenge.challenger_score.is_none() && challenge.opponent_score.is_none() =>
+            {
+                challenge.status = ChallengeStatus::Expired;
+                challenge.completed_at = Some(self.runtime.system_time().micros());
+                true
+            }
+            ChallengeStatus::Accepted => {
+                self.complete_challenge(challenge).await;
+                true
+            }
+            ChallengeStatus::Completed | ChallengeStatus::Expired => false,
+        }
+    }
+
+    async fn complete_challenge(&mut self, challenge: &mut Challenge) {
+        let timestamp = self.runtime.system_time().micros();
+        challenge.status = ChallengeStatus::Completed;
+        challenge.winner = challenge.decide_winner();
+        challenge.completed_at = Some(timestamp);
+
+        // Update the head-to-head record for the pair
+        let key = head_to_head_key(&challenge.challenger, &challenge.opponent);
+        let mut record = self
+            .state
+            .head_to_head
+            .get(&key)
+            .await
+            .expect("Failed to get head-to-head record")
+            .unwrap_or_default();
+        record.player_a = key.0.clone();
+        record.player_b = key.1.clone();
+        record.record(challenge.winner.as_deref(), timestamp);
+        self.state
+            .head_to_head
+            .insert(&key, record)
+            .expect("Failed to update head-to-head record");
+    }
+
+    async fn save_challenge(&mut self, challenge: Challenge) {
+        self.state
+            .challenges
+            .insert(&challenge.id, challenge.clone())
+            .expect("Failed to update challenge");
+
+        // Keep both players' local caches in sync
+        for username in [&challenge.challenger, &challenge.opponent] {
+            let Some(chain_id) = self.user_chain_id(username).await else {
+                continue;
+            };
+            if chain_id == self.runtime.chain_id() {
+                self.state
+                    .my_challenges
+                    .insert(&challenge.id, challenge.clone())
+                    .expect("Failed to update challenge cache");
+            } else {
+                let update = FlappyMessage::ChallengeUpdate {
+                    challenge: challenge.clone(),
+                };
+                self.runtime.prepare_message(update).send_to(chain_id);
+            }
+        }
+    }
+
+    async fn process_tournament_score(
+        &mut self,
+        tournament_id: String,
+        username: String,
+        score: u64,
+        player_chain_id: ChainId,
+        ghost: Option<Vec<u8>>,
+        submission_gap: Option<u64>,
+    ) {
+        // Get tournament and validate it's active
+        let tournament = match self.state.tournaments.get(&tournament_id).await {
+            Ok(Some(tournament)) => tournament,
+            _ => return, // Tournament not found
+        };
+
+        if tournament.status != TournamentStatus::Active {
+            return; // Tournament not active
+        }
+
+        if !tournament.participants.contains(&username) {
+            return; // User not registered for this tournament
+        }
+
+        if self.active_restriction(&username).await.is_some() {
+            return; // Banned, suspended or deleted account
+        }
+
+        // Hold suspicious scores back until an admin reviews them
+        if self
+            .moderate_score(
+                &username,
+                score,
+                Some(&tournament_id),
+                player_chain_id,
+                &ghost,
+                submission_gap,
+            )
+            .await
+        {
+            return;
+        }
+
+        self.apply_tournament_score(tournament_id, username, score, player_chain_id, ghost)
+            .await;
+    }
+
+    async fn apply_tournament_score(
+        &mut self,
+        tournament_id: String,
+        username: String,
+        score: u64,
+        player_chain_id: ChainId,
+        ghost: Option<Vec<u8>>,
+    ) {
+        // Update live tournament leaderboard directly
+        let is_new_best = self
+            .update_tournament_leaderboard_with_score(
+                &tournament_id,
+                username.clone(),
+                score,
+                player_chain_id,
+            )
+            .await;
+
+        // Keep the ghost of the player's best run in this tournament
+        if is_new_best {
+            let key = (tournament_id.clone(), username.clone());
+            let recorded_at = self.runtime.system_time().micros();
+            match self.ghost_run(score, ghost, recorded_at) {
+                Some(ghost_run) => self
+                    .state
+                    .tournament_ghosts
+                    .insert(&key, ghost_run)
+                    .expect("Failed to store ghost run"),
+                None => self
+                    .state
+                    .tournament_ghosts
+                    .remove(&key)
+                    .expect("Failed to remove ghost run"),
+            }
+        }
+
+        self.check_score_achievements(&username, score, Some(&tournament_id))
+            .await;
+    }
+
+    // Closes the run ticket, panics if the run could not have been played since the ticket
+    // was issued or since this chain's previous submission
+    fn record_submission(&mut self, score: u64, ticket_id: u64) -> (u64, RunTicket) {
+        let now = self.runtime.system_time().micros();
+        let game = self.runtime.application_parameters().game;
+        let ticket = match self.state.open_run_ticket.get() {
+            Some(ticket) if ticket.id == ticket_id => ticket.clone(),
+            _ => panic!("Run ticket {} is not open", ticket_id),
+        };
+        if !ticket.allows(&game, score, now) {
+            panic!(
+                "Run ticket {} was issued too recently for a score of {}",
+                ticket_id, score
+            );
+        }
+        if !submission_allowed(&game, score, *self.state.last_submission_at.get(), now) {
+            panic!("Score submitted too soon after the previous run");
+        }
+        self.state.open_run_ticket.set(None);
+        self.state.last_submission_at.set(Some(now));
+        (now, ticket)
+    }
+
+    // Same checks on the leaderboard chain, using the player chain's submission time
+    // Time from the chain's previous accepted submission to this one, None for its first
+    async fn submission_gap(&self, chain_id: ChainId, submitted_at: u64) -> Option<u64> {
+        self.state
+            .chain_last_submissions
+            .get(&chain_id)
+            .await
+            .expect("Failed to get last chain submission")
+            .map(|last_submission_at| submitted_at.saturating_sub(last_submission_at))
+    }
+
+    async fn accept_chain_submission(
+        &mut self,
+        chain_id: ChainId,
+        score: u64,
+        submitted_at: u64,
+        ticket: &RunTicket,
+    ) -> bool {
+        let game = self.runtime.application_parameters().game;
+        if !ticket.allows(&game, score, submitted_at) {
+            return false;
+        }
+
+        // Ticket IDs only grow on a player chain, so a lower one was already used
+        let last_ticket_id = self
+            .state
+            .chain_last_tickets
+            .get(&chain_id)
+            .await
+            .expect("Failed to get last run ticket");
+        if last_ticket_id.is_some_and(|last_ticket_id| ticket.id <= last_ticket_id) {
+            return false;
+        }
+
+        let last_submission_at = self
+            .state
+            .chain_last_submissions
+            .get(&chain_id)
+            .await
+            .expect("Failed to get last chain submission");
+        if !submission_allowed(&game, score, last_submission_at, submitted_at) {
+            return false;
+        }
+        self.state
+            .chain_last_submissions
+            .insert(&chain_id, submitted_at)
+            .expect("Failed to record chain submission");
+        self.state
+            .chain_last_tickets
+            .insert(&chain_id, ticket.id)
+            .expect("Failed to record run ticket");
+        true
+    }
+
+    async fn apply_practice_best(
+        &mut self,
+        username: String,
+        score: u64,
+        player_chain_id: ChainId,
+        ghost: Option<Vec<u8>>,
+    ) {
+        // Create practice entry with current timestamp
+        let timestamp = self.runtime.system_time().micros();
+        let practice_entry = PracticeEntry {
+            username: username.clone(),
+            score,
+            chain_id: player_chain_id,
+            timestamp,
+        };
+
+        // Update user's best practice score
+        self.state
+            .practice_best_scores
+            .insert(&username, practice_entry)
+            .expect("Failed to update practice best score");
+
+        // Replace the ghost of the previous best
+        match self.ghost_run(score, ghost, timestamp) {
+            Some(ghost_run) => self
+                .state
+                .practice_ghosts
+                .insert(&username, ghost_run)
+                .expect("Failed to store ghost run"),
+            None => self
+                .state
+                .practice_ghosts
+                .remove(&username)
+                .expect("Failed to remove ghost run"),
+        }
+
+        // Update global top 100 practice leaderboard
+        self.update_practice_leaderboard().await;
+
+        let top_entry = self
+            .state
+            .practice_leaderboard
+            .get()
+            .iter()
+            .position(|entry| entry.username == username)
+            .map(|index| (index, self.state.practice_leaderboard.get()[index].clone()));
+        if let Some((index, entry)) = top_entry {
+            self.emit_event(FlappyEvent::PracticeTopEntry {
+                entry,
+                rank: (index + 1) as u32,
+            });
+        }
+
+        self.check_score_achievements(&username, score, None).await;
+    }
+
+    // Queues the score for review if it trips a moderation threshold, returns true when held back
+    async fn moderate_score(
+        &mut self,
+        username: &str,
+        score: u64,
+        tournament_id: Option<&str>,
+        player_chain_id: ChainId,
+        ghost: &Option<Vec<u8>>,
+        submission_gap: Option<u64>,
+    ) -> bool {
+        // Personal best across practice and the tournament the score belongs to
+        let mut personal_best = self
+            .state
+            .practice_best_scores
+            .get(username)
+            .await
+            .expect("Failed to get practice best score")
+            .map(|entry| entry.score);
+        if let Some(tournament_id) = tournament_id {
+            if let Ok(Some(leaderboard)) =
+                self.state.tournament_leaderboards.get(tournament_id).await
+            {
+                if let Some(result) = leaderboard.iter().find(|r| r.username == username) {
+                    personal_best = personal_best.max(Some(result.score));
+                }
+            }
+        }
+
+        let reasons = self.state.moderation_thresholds.get().flag_reasons(
+            score,
+            personal_best,
+            submission_gap,
+        );
+        if reasons.is_empty() {
+            return false;
+        }
+
+        let counter = *self.state.pending_score_counter.get() + 1;
+        self.state.pending_score_counter.set(counter);
+        let pending_id = format!("pending_{}", counter);
+        let pending = PendingScore {
+            id: pending_id.clone(),
+            username: username.to_string(),
+            score,
+            tournament_id: tournament_id.map(str::to_string),
+            chain_id: player_chain_id,
+            submitted_at: self.runtime.system_time().micros(),
+            reasons,
+            ghost: ghost.clone(),
+        };
+        self.state
+            .pending_scores
+            .insert(&pending_id, pending)
+            .expect("Failed to queue pending score");
+        true
+    }
+
+    async fn take_pending_score(&mut self, pending_id: &str) -> PendingScore {
+        let pending = self
+            .state
+            .pending_scores
+            .get(pending_id)
+            .await
+            .expect("Failed to get pending score")
+            .unwrap_or_else(|| panic!("Pending score '{}' not found", pending_id));
+        self.state
+            .pending_scores
+            .remove(pending_id)
+            .expect("Failed to remove pending score");
+        pending
+    }
+
+    async fn unlock_achievement(
+        &mut self,
+        username: &str,
+        kind: AchievementKind,
+        tournament_id: Option<&str>,
+    ) {
+        let mut unlocked = self
+            .state
+            .achievements
+            .get(username)
+            .await
+            .expect("Failed to get achievements")
+            .unwrap_or_default();
+
+        if unlocked.iter().any(|achievement| achievement.kind == kind) {
+            return;
+        }
+
+        let achievement = Achievement {
+            kind,
+            unlocked_at: self.runtime.system_time().micros(),
+            tournament_id: tournament_id.map(str::to_string),
+        };
+        unlocked.push(achievement.clone());
+        self.state
+            .achievements
+            .insert(username, unlocked)
+            .expect("Failed to update achievements");
+
+        self.emit_event(FlappyEvent::AchievementUnlocked {
+            username: username.to_string(),
+            achievement,
+        });
+    }
+
+    fn emit_event(&mut self, event: FlappyEvent) {
+        self.runtime
+            .emit(StreamName::from(FLAPPY_STREAM_NAME), &event);
+    }
+
+    // Picks up the leaderboard chain from the application parameters on the chain's first block
+    fn configure_from_parameters(&mut self) {
+        if self.state.leaderboard_chain_id.get().is_some() {
+            return;
+        }
+        if let Some(leaderboard_chain_id) =
+            self.runtime.application_parameters().leaderboard_chain_id
+        {
+            self.configure_leaderboard_chain(leaderboard_chain_id);
+        }
+    }
+
+    fn configure_leaderboard_chain(&mut self, leaderboard_chain_id: ChainId) {
+        self.state
+            .leaderboard_chain_id
+            .set(Some(leaderboard_chain_id));
+
+        // If this chain is being designated as the leaderboard chain
+        if self.runtime.chain_id() == leaderboard_chain_id {
+            self.state.is_leaderboard_chain.set(true);
+        } else {
+            // Player chains keep a read replica of the boards from the leaderboard chain's events
+            let application_id = self.runtime.application_id().forget_abi();
+            self.runtime.subscribe_to_events(
+                leaderboard_chain_id,
+                application_id,
+                StreamName::from(FLAPPY_STREAM_NAME),
+            );
+        }
+    }
+
+    // Runs one batch of the next pending schema upgrade, so no block does unbounded work
+    async fn migrate_state(&mut self) {
+        let version = *self.state.schema_version.get();
+        if version >= SCHEMA_VERSION {
+            return;
+        }
+
+        let finished = match version {
+            0 => self.migrate_legacy_scores().await,
+            1 => self.migrate_user_credentials().await,
+            2 => self.publish_existing_tournaments().await,
+            3 => self.count_tournament_entries().await,
+            4 => self.index_usernames().await,
+            5 => self.subscribe_to_leaderboard_events(),
+            6 => self.seed_reserved_usernames(),
+            _ => unreachable!("Unknown schema version {}", version),
+        };
+        if finished {
+            self.state.schema_version.set(version + 1);
+            self.state.migration_cursor.set(None);
+        }
+    }
+
+    // Next keys of a migration step, moving the cursor past them. The cursor is tagged with
+    // the step so a step never resumes from a key another step left behind
+    fn next_migration_batch(&mut self, keys: Vec<String>) -> Vec<String> {
+        let step = format!("{}:", self.state.schema_version.get());
+        let cursor = self
+            .state
+            .migration_cursor
+            .get()
+            .as_deref()
+            .and_then(|cursor| cursor.strip_prefix(&step))
+            .map(str::to_string);
+        let batch = migration_batch(keys, cursor.as_deref(), MIGRATION_BATCH_SIZE);
+        if let Some(last) = batch.last() {
+            self.state
+                .migration_cursor
+                .set(Some(format!("{}{}", step, last)));
+        }
+        batch
+    }
+
+    // Moves scores from the legacy high score board into practice bests, keeping the higher one
+    async fn migrate_legacy_scores(&mut self) -> bool {
+        let usernames = self
+            .state
+            .legacy_player_scores
+            .indices()
+            .await
+            .expect("Failed to get legacy scores");
+        let finished = usernames.len() <= MIGRATION_BATCH_SIZE;
+
+        // Only the leaderboard chain ranks practice bests, player chains just drop their copy
+        let is_leaderboard = *self.state.is_leaderboard_chain.get();
+        for username in migration_batch(usernames, None, MIGRATION_BATCH_SIZE) {
+            let legacy = self
+                .state
+                .legacy_player_scores
+                .get(&username)
+                .await
+                .expect("Failed to get legacy score");
+            self.state
+                .legacy_player_scores
+                .remove(&username)
+                .expect("Failed to remove legacy score");
+            let Some(legacy) = legacy.filter(|_| is_leaderboard) else {
+                continue;
+            };
+
+            let current = self
+                .state
+                .practice_best_scores
+                .get(&username)
+                .await
+                .expect("Failed to get practice best score");
+            if current.is_some_and(|entry| entry.score >= legacy.score) {
+                continue;
+            }
+            let entry = PracticeEntry {
+                username: username.clone(),
+                score: legacy.score,
+                chain_id: legacy.chain_id,
+                timestamp: legacy.timestamp,
+            };
+            self.state
+                .practice_best_scores
+                .insert(&username, entry)
+                .expect("Failed to migrate legacy score");
+        }
+
+        if finished {
+            if is_leaderboard {
+                self.reset_practice_leaderboard().await;
+            }
+            self.state.legacy_top_leaderboard.set(Vec::new());
+            self.migrate_legacy_practice_scores();
+        }
+        finished
+    }
+
+    // Salts the credentials of users who haven't logged in since plain hashes were stored
+    async fn migrate_user_credentials(&mut self) -> bool {
+        // Copies of users held by login state only ever need their legacy hash dropped
+        if let Some(mut user) = self.state.current_user.get().clone() {
+            if !user.hash.is_empty() {
+                user.hash.clear();
+                self.state.current_user.set(Some(user));
+            }
+        }
+
+        // Keys are tagged with their kind, so usernames and chain ids share one sorted cursor
+        // without a username that looks like a chain id being taken for one
+        let usernames = self
+            .state
+            .users
+            .indices()
+            .await
+            .expect("Failed to get usernames");
+        let login_chain_ids = self
+            .state
+            .login_results
+            .indices()
+            .await
+            .expect("Failed to get login results");
+        let keys = usernames
+            .iter()
+            .map(|username| format!("u:{}", username))
+            .chain(
+                login_chain_ids
+                    .iter()
+                    .map(|chain_id| format!("c:{}", chain_id)),
+            )
+            .collect();
+        let batch = self.next_migration_batch(keys);
+
+        for key in &batch {
+            if let Some(chain_id) = key.strip_prefix("c:") {
+                let chain_id = chain_id.parse().expect("Failed to parse chain ID");
+                self.clear_login_result_hash(chain_id).await;
+                continue;
+            }
+            let username = key.strip_prefix("u:").expect("Unknown migration key");
+            let Some(mut user) = self
+                .state
+                .users
+                .get(username)
+                .await
+                .expect("Failed to get user")
+            else {
+                continue;
+            };
+            if user.hash.is_empty() {
+                continue;
+            }
+
+            let client_hash = user.hash.clone();
+            self.set_credential(&mut user, &client_hash);
+            self.state
+                .users
+                .insert(username, user)
+                .expect("Failed to update user");
+        }
+        batch.len() < MIGRATION_BATCH_SIZE
+    }
+
+    // Drops the legacy client hash from the user a stored login result carries
+    async fn clear_login_result_hash(&mut self, chain_id: ChainId) {
+        let Some(mut result) = self
+            .state
+            .login_results
+            .get(&chain_id)
+            .await
+            .expect("Failed to get login result")
+        else {
+            return;
+        };
+        let Some(user) = result.user.as_mut().filter(|user| !user.hash.is_empty()) else {
+            return;
+        };
+        user.hash.clear();
+        self.state
+            .login_results
+            .insert(&chain_id, result)
+            .expect("Failed to update login result");
+    }
+
+    // Publishes tournaments created before the event stream so player chain replicas see them
+    async fn publish_existing_tournaments(&mut self) -> bool {
+        if !*self.state.is_leaderboard_chain.get() {
+            return true;
+        }
+
+        let tournament_ids = self
+            .state
+            .tournaments
             .indices()
             .await
-            .expect("Failed to get practice score usernames");
+            .expect("Failed to get tournament IDs");
+        let batch = self.next_migration_batch(tournament_ids);
 
-        for username in usernames {
-            if let Ok(Some(entry)) = self.state.practice_best_scores.get(&username).await {
-                all_practice_scores.push(entry);
+        for tournament_id in &batch {
+            if let Some(tournament) = self
+                .state
+                .tournaments
+                .get(tournament_id)
+                .await
+                .expect("Failed to get tournament")
+            {
+                self.emit_event(FlappyEvent::TournamentUpdated { tournament });
+            }
+            if let Some(leaderboard) = self
+                .state
+                .tournament_leaderboards
+                .get(tournament_id)
+                .await
+                .expect("Failed to get tournament leaderboard")
+            {
+                self.emit_event(FlappyEvent::TournamentLeaderboardReset {
+                    tournament_id: tournament_id.clone(),
+                    leaderboard,
+                });
             }
         }
 
-        // Sort by score descending (highest first)
-        all_practice_scores.sort_by(|a, b| b.score.cmp(&a.score));
+        let finished = batch.len() < MIGRATION_BATCH_SIZE;
+        if finished {
+            let leaderboard = self.state.practice_leaderboard.get().clone();
+            self.emit_event(FlappyEvent::PracticeLeaderboardReset { leaderboard });
+        }
+        finished
+    }
 
-        // Take top 100 and update the leaderboard
-        let top_100: Vec<PracticeEntry> = all_practice_scores.into_iter().take(100).collect();
+    // Counts the tournaments each player joined before entries were counted on joining
+    async fn count_tournament_entries(&mut self) -> bool {
+        if !*self.state.is_leaderboard_chain.get() {
+            return true;
+        }
 
-        self.state.practice_leaderboard.set(top_100);
+        let tournament_ids = self
+            .state
+            .tournaments
+            .indices()
+            .await
+            .expect("Failed to get tournament IDs");
+        let batch = self.next_migration_batch(tournament_ids);
+
+        for tournament_id in &batch {
+            let Some(tournament) = self
+                .state
+                .tournaments
+                .get(tournament_id)
+                .await
+                .expect("Failed to get tournament")
+            else {
+                continue;
+            };
+            for username in &tournament.participants {
+                let entered = self
+                    .state
+                    .tournaments_entered
+                    .get(username)
+                    .await
+                    .expect("Failed to get tournament entries")
+                    .unwrap_or(0);
+                self.state
+                    .tournaments_entered
+                    .insert(username, entered + 1)
+                    .expect("Failed to update tournament entries");
+            }
+        }
+        batch.len() < MIGRATION_BATCH_SIZE
     }
 
-    async fn process_tournament_score(
+    // Indexes the usernames registered before lookups were case-insensitive
+    async fn index_usernames(&mut self) -> bool {
+        let usernames = self
+            .state
+            .users
+            .indices()
+            .await
+            .expect("Failed to get usernames");
+        let batch = self.next_migration_batch(usernames);
+
+        for username in &batch {
+            self.index_username(username);
+        }
+        batch.len() < MIGRATION_BATCH_SIZE
+    }
+
+    // Subscribes a player chain configured before the event stream existed to the leaderboard
+    // chain's events, so its replica starts following the boards
+    fn subscribe_to_leaderboard_events(&mut self) -> bool {
+        if *self.state.is_leaderboard_chain.get() {
+            return true;
+        }
+        if let Some(leaderboard_chain_id) = *self.state.leaderboard_chain_id.get() {
+            let application_id = self.runtime.application_id().forget_abi();
+            self.runtime.subscribe_to_events(
+                leaderboard_chain_id,
+                application_id,
+                StreamName::from(FLAPPY_STREAM_NAME),
+            );
+        }
+        true
+    }
+
+    // Seeds the default reserved usernames on deployments from before names were reserved
+    fn seed_reserved_usernames(&mut self) -> bool {
+        if self.state.reserved_usernames.get().is_empty() {
+            self.state.reserved_usernames.set(
+                DEFAULT_RESERVED_USERNAMES
+                    .iter()
+                    .map(|name| name.to_string())
+                    .collect(),
+            );
+        }
+        true
+    }
+
+    // Stores a tournament and publishes it for player chain replicas
+    fn save_tournament(&mut self, tournament: Tournament) {
+        self.state
+            .tournaments
+            .insert(&tournament.id, tournament.clone())
+            .expect("Failed to update tournament");
+        self.emit_event(FlappyEvent::TournamentUpdated { tournament });
+    }
+
+    // Rebuilds the top 100 after moderation and publishes it whole, since replicas
+    // can't tell from single entries which scores were removed or restored
+    async fn reset_practice_leaderboard(&mut self) {
+        self.update_practice_leaderboard().await;
+        let leaderboard = self.state.practice_leaderboard.get().clone();
+        self.emit_event(FlappyEvent::PracticeLeaderboardReset { leaderboard });
+    }
+
+    // Applies an event from the leaderboard chain to this player chain's read replica
+    async fn apply_leaderboard_event(&mut self, event: FlappyEvent) {
+        match event {
+            FlappyEvent::PracticeTopEntry { entry, rank } => {
+                let mut leaderboard = self.state.practice_leaderboard.get().clone();
+                insert_practice_top_entry(&mut leaderboard, entry, rank);
+                self.state.practice_leaderboard.set(leaderboard);
+            }
+            FlappyEvent::PracticeLeaderboardReset { leaderboard } => {
+                self.state.practice_leaderboard.set(leaderboard);
+            }
+            FlappyEvent::TournamentUpdated { tournament } => {
+                self.state
+                    .tournaments
+                    .insert(&tournament.id.clone(), tournament)
+                    .expect("Failed to update tournament replica");
+            }
+            FlappyEvent::TournamentDeleted { tournament_id } => {
+                self.state
+                    .tournaments
+                    .remove(&tournament_id)
+                    .expect("Failed to remove tournament replica");
+                self.state
+                    .tournament_leaderboards
+                    .remove(&tournament_id)
+                    .expect("Failed to remove tournament leaderboard replica");
+            }
+            FlappyEvent::TournamentScoreUpdated {
+                tournament_id,
+                result,
+            } => {
+                let mut leaderboard = self
+                    .state
+                    .tournament_leaderboards
+                    .get(&tournament_id)
+                    .await
+                    .expect("Failed to get tournament leaderboard replica")
+                    .unwrap_or_default();
+                leaderboard.retain(|r| r.username != result.username);
+                leaderboard.push(result);
+                rank_tournament_results(&mut leaderboard);
+                self.state
+                    .tournament_leaderboards
+                    .insert(&tournament_id, leaderboard)
+                    .expect("Failed to update tournament leaderboard replica");
+            }
+            FlappyEvent::TournamentLeaderboardReset {
+                tournament_id,
+                leaderboard,
+            } => {
+                self.state
+                    .tournament_leaderboards
+                    .insert(&tournament_id, leaderboard)
+                    .expect("Failed to update tournament leaderboard replica");
+            }
+            FlappyEvent::LeaderboardChainMoved {
+                leaderboard_chain_id,
+            } => {
+                self.follow_leaderboard_chain(leaderboard_chain_id);
+            }
+            // Status changes arrive with the full tournament in TournamentUpdated
+            FlappyEvent::TournamentStatusChanged { .. }
+            | FlappyEvent::AchievementUnlocked { .. }
+            | FlappyEvent::UserRegistered { .. } => {}
+        }
+    }
+
+    fn leaderboard_move_in_progress(&self) -> bool {
+        self.state
+            .leaderboard_move
+            .get()
+            .as_ref()
+            .is_some_and(|leaderboard_move| leaderboard_move.step != LeaderboardMoveStep::Completed)
+    }
+
+    // Holds messages while the leaderboard chain moves, so they reach the new chain after the
+    // state they apply to, and passes them on once it has moved. Returns true if redirected
+    fn redirect_moved_message(&mut self, message: &FlappyMessage) -> bool {
+        let Some(leaderboard_move) = self.state.leaderboard_move.get().clone() else {
+            return false;
+        };
+        if self.runtime.message_origin_chain_id() == Some(leaderboard_move.new_chain_id) {
+            return false;
+        }
+
+        // The new chain checks senders, so messages keep the chain they came from
+        let forwarded = FlappyMessage::Forwarded {
+            origin_chain_id: self
+                .message_origin_chain_id()
+                .expect("Message origin must be available"),
+            message: Box::new(message.clone()),
+        };
+        if leaderboard_move.step == LeaderboardMoveStep::Completed {
+            self.runtime
+                .prepare_message(forwarded)
+                .send_to(leaderboard_move.new_chain_id);
+        } else {
+            self.state.held_messages.push_back(forwarded);
+        }
+        true
+    }
+
+    // Stops the move and handles the messages held during it here, as if they had just arrived
+    async fn cancel_leaderboard_move(&mut self) {
+        self.state.leaderboard_move.set(None);
+        let held = self
+            .state
+            .held_messages
+            .elements()
+            .await
+            .expect("Failed to get held messages");
+        self.state.held_messages.clear();
+
+        for message in held {
+            let FlappyMessage::Forwarded {
+                origin_chain_id,
+                message,
+            } = message
+            else {
+                continue;
+            };
+            self.forwarded_origin = Some(origin_chain_id);
+            Box::pin(self.handle_message(*message)).await;
+        }
+        self.forwarded_origin = None;
+    }
+
+    // Sends the next batch of state to the new leaderboard chain
+    async fn advance_leaderboard_move(&mut self) {
+        let Some(mut leaderboard_move) = self.state.leaderboard_move.get().clone() else {
+            return;
+        };
+        let new_chain_id = leaderboard_move.new_chain_id;
+
+        match leaderboard_move.step {
+            LeaderboardMoveStep::Users => {
+                let usernames = self
+                    .state
+                    .users
+                    .indices()
+                    .await
+                    .expect("Failed to get usernames");
+                let batch =
+                    leaderboard_move.next_batch(usernames, LeaderboardMoveStep::Tournaments);
+
+                let mut users = Vec::new();
+                for username in &batch {
+                    if let Some(snapshot) = self.user_snapshot(username).await {
+                        users.push(snapshot);
+                    }
+                }
+                if !users.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardUsers { users })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::Tournaments => {
+                let tournament_ids = self
+                    .state
+                    .tournaments
+                    .indices()
+                    .await
+                    .expect("Failed to get tournament IDs");
+                let batch =
+                    leaderboard_move.next_batch(tournament_ids, LeaderboardMoveStep::Challenges);
+
+                let mut tournaments = Vec::new();
+                for tournament_id in &batch {
+                    if let Some(snapshot) = self.tournament_snapshot(tournament_id).await {
+                        tournaments.push(snapshot);
+                    }
+                }
+                if !tournaments.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardTournaments { tournaments })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::Challenges => {
+                let challenge_ids = self
+                    .state
+                    .challenges
+                    .indices()
+                    .await
+                    .expect("Failed to get challenge IDs");
+                let batch =
+                    leaderboard_move.next_batch(challenge_ids, LeaderboardMoveStep::HeadToHead);
+
+                let mut challenges = Vec::new();
+                for challenge_id in &batch {
+                    if let Some(challenge) = self
+                        .state
+                        .challenges
+                        .get(challenge_id)
+                        .await
+                        .expect("Failed to get challenge")
+                    {
+                        challenges.push(challenge);
+                    }
+                }
+                if !challenges.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardChallenges { challenges })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::HeadToHead => {
+                // Pairs are JSON encoded in the move cursor, usernames may hold any separator
+                let pairs = self
+                    .state
+                    .head_to_head
+                    .indices()
+                    .await
+                    .expect("Failed to get head-to-head pairs")
+                    .iter()
+                    .map(|pair| serde_json::to_string(pair).expect("Failed to encode pair"))
+                    .collect();
+                let batch = leaderboard_move.next_batch(pairs, LeaderboardMoveStep::PendingScores);
+
+                let mut records = Vec::new();
+                for key in &batch {
+                    let pair: (String, String) =
+                        serde_json::from_str(key).expect("Failed to decode pair");
+                    if let Some(record) = self
+                        .state
+                        .head_to_head
+                        .get(&pair)
+                        .await
+                        .expect("Failed to get head-to-head record")
+                    {
+                        records.push((pair, record));
+                    }
+                }
+                if !records.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardHeadToHead { records })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::PendingScores => {
+                let pending_ids = self
+                    .state
+                    .pending_scores
+                    .indices()
+                    .await
+                    .expect("Failed to get pending score IDs");
+                let batch =
+                    leaderboard_move.next_batch(pending_ids, LeaderboardMoveStep::LoginAttempts);
+
+                let mut pending_scores = Vec::new();
+                for pending_id in &batch {
+                    if let Some(pending) = self
+                        .state
+                        .pending_scores
+                        .get(pending_id)
+                        .await
+                        .expect("Failed to get pending score")
+                    {
+                        pending_scores.push(pending);
+                    }
+                }
+                if !pending_scores.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardPendingScores {
+                            pending_scores,
+                        })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::LoginAttempts => {
+                // Failed logins per username move with the users, these are per requesting chain
+                let chain_ids = self
+                    .state
+                    .chain_login_attempts
+                    .indices()
+                    .await
+                    .expect("Failed to get chain login attempts")
+                    .iter()
+                    .map(ChainId::to_string)
+                    .collect();
+                let batch = leaderboard_move.next_batch(chain_ids, LeaderboardMoveStep::Boards);
+
+                let mut chain_attempts = Vec::new();
+                for key in &batch {
+                    let chain_id = key
+                        .parse::<ChainId>()
+                        .expect("Failed to parse the chain ID");
+                    if let Some(attempts) = self
+                        .state
+                        .chain_login_attempts
+                        .get(&chain_id)
+                        .await
+                        .expect("Failed to get chain login attempts")
+                    {
+                        chain_attempts.push((chain_id, attempts));
+                    }
+                }
+                if !chain_attempts.is_empty() {
+                    self.runtime
+                        .prepare_message(FlappyMessage::MoveLeaderboardLoginAttempts {
+                            chain_attempts,
+                        })
+                        .send_to(new_chain_id);
+                }
+            }
+            LeaderboardMoveStep::Boards => {
+                let boards = BoardsSnapshot {
+                    practice_leaderboard: self.state.practice_leaderboard.get().clone(),
+                    global_practice_stats: self.state.global_practice_stats.get().clone(),
+                    pinned_tournaments: self.state.pinned_tournaments.get().clone(),
+                    tournament_counter: *self.state.tournament_counter.get(),
+                    challenge_counter: *self.state.challenge_counter.get(),
+                    pending_score_counter: *self.state.pending_score_counter.get(),
+                    reserved_usernames: self.state.reserved_usernames.get().clone(),
+                    moderation_thresholds: self.state.moderation_thresholds.get().clone(),
+                };
+                self.runtime
+                    .prepare_message(FlappyMessage::MoveLeaderboardBoards { boards })
+                    .send_to(new_chain_id);
+                leaderboard_move.step = LeaderboardMoveStep::AwaitingNewChain;
+            }
+            LeaderboardMoveStep::AwaitingNewChain | LeaderboardMoveStep::Completed => {}
+        }
+
+        self.state.leaderboard_move.set(Some(leaderboard_move));
+    }
+
+    async fn user_snapshot(&self, username: &str) -> Option<UserSnapshot> {
+        let user = self
+            .state
+            .users
+            .get(username)
+            .await
+            .expect("Failed to get user")?;
+
+        // Login sessions and submission limits are kept per chain, only the user's own one moves
+        let mut session = None;
+        let mut chain_last_submission = None;
+        let mut chain_last_ticket = None;
+        if let Some(chain_id) = user.chain_id {
+            session = self
+                .state
+                .login_results
+                .get(&chain_id)
+                .await
+                .expect("Failed to get login result")
+                .filter(|result| {
+                    result
+                        .user
+                        .as_ref()
+                        .is_some_and(|session_user| session_user.username == username)
+                });
+            chain_last_submission = self
+                .state
+                .chain_last_submissions
+                .get(&chain_id)
+                .await
+                .expect("Failed to get chain submission time");
+            chain_last_ticket = self
+                .state
+                .chain_last_tickets
+                .get(&chain_id)
+                .await
+                .expect("Failed to get chain ticket");
+        }
+
+        Some(UserSnapshot {
+            credential: self
+                .state
+                .credentials
+                .get(username)
+                .await
+                .expect("Failed to get credential"),
+            restriction: self
+                .state
+                .account_restrictions
+                .get(username)
+                .await
+                .expect("Failed to get account restriction"),
+            practice_best: self
+                .state
+                .practice_best_scores
+                .get(username)
+                .await
+                .expect("Failed to get practice best score"),
+            practice_stats: self
+                .state
+                .practice_stats
+                .get(username)
+                .await
+                .expect("Failed to get practice stats"),
+            practice_ghost: self
+                .state
+                .practice_ghosts
+                .get(username)
+                .await
+                .expect("Failed to get practice ghost"),
+            achievements: self
+                .state
+                .achievements
+                .get(username)
+                .await
+                .expect("Failed to get achievements")
+                .unwrap_or_default(),
+            friends: self
+                .state
+                .friendships
+                .get(username)
+                .await
+                .expect("Failed to get friendships")
+                .unwrap_or_default(),
+            credential_reset: self
+                .state
+                .credential_resets
+                .get(username)
+                .await
+                .expect("Failed to get credential reset"),
+            login_attempts: self
+                .state
+                .login_attempts
+                .get(username)
+                .await
+                .expect("Failed to get login attempts"),
+            tournaments_entered: self
+                .state
+                .tournaments_entered
+                .get(username)
+                .await
+                .expect("Failed to get tournaments entered")
+                .unwrap_or_default(),
+            hidden_tournament_results: self
+                .state
+                .hidden_tournament_results
+                .get(username)
+                .await
+                .expect("Failed to get hidden tournament results")
+                .unwrap_or_default(),
+            user,
+            session,
+            chain_last_submission,
+            chain_last_ticket,
+        })
+    }
+
+    async fn tournament_snapshot(&self, tournament_id: &str) -> Option<TournamentSnapshot> {
+        let tournament = self
+            .state
+            .tournaments
+            .get(tournament_id)
+            .await
+            .expect("Failed to get tournament")?;
+        let leaderboard = self
+            .state
+            .tournament_leaderboards
+            .get(tournament_id)
+            .await
+            .expect("Failed to get tournament leaderboard");
+
+        let mut ghosts = Vec::new();
+        for username in &tournament.participants {
+            let key = (tournament_id.to_string(), username.clone());
+            if let Some(ghost) = self
+                .state
+                .tournament_ghosts
+                .get(&key)
+                .await
+                .expect("Failed to get tournament ghost")
+            {
+                ghosts.push((username.clone(), ghost));
+            }
+        }
+
+        Some(TournamentSnapshot {
+            tournament,
+            leaderboard,
+            ghosts,
+        })
+    }
+
+    // Move state is only taken from the leaderboard chain this chain follows, before taking over.
+    // Other senders are turned down, so their move doesn't wait on this chain forever
+    fn accept_leaderboard_move(&mut self) -> bool {
+        let origin_chain_id = self.runtime.message_origin_chain_id();
+        if !*self.state.is_leaderboard_chain.get()
+            && self.state.leaderboard_chain_id.get().is_some()
+            && origin_chain_id == *self.state.leaderboard_chain_id.get()
+        {
+            return true;
+        }
+        if let Some(origin_chain_id) = origin_chain_id {
+            self.runtime
+                .prepare_message(FlappyMessage::LeaderboardMoveRejected)
+                .send_to(origin_chain_id);
+        }
+        false
+    }
+
+    fn restore_user(&mut self, snapshot: UserSnapshot) {
+        let username = snapshot.user.username.clone();
+        if let Some(chain_id) = snapshot.user.chain_id {
+            if let Some(session) = snapshot.session {
+                self.state
+                    .login_results
+                    .insert(&chain_id, session)
+                    .expect("Failed to restore login session");
+            }
+            if let Some(submitted_at) = snapshot.chain_last_submission {
+                self.state
+                    .chain_last_submissions
+                    .insert(&chain_id, submitted_at)
+                    .expect("Failed to restore chain submission time");
+            }
+            if let Some(ticket_id) = snapshot.chain_last_ticket {
+                self.state
+                    .chain_last_tickets
+                    .insert(&chain_id, ticket_id)
+                    .expect("Failed to restore chain ticket");
+            }
+        }
+        if let Some(credential) = snapshot.credential {
+            self.state
+                .credentials
+                .insert(&username, credential)
+                .expect("Failed to restore credential");
+        }
+        if let Some(restriction) = snapshot.restriction {
+            self.state
+                .account_restrictions
+                .insert(&username, restriction)
+                .expect("Failed to restore account restriction");
+        }
+        if let Some(entry) = snapshot.practice_best {
+            self.state
+                .practice_best_scores
+                .insert(&username, entry)
+                .expect("Failed to restore practice best score");
+        }
+        if let Some(stats) = snapshot.practice_stats {
+            self.state
+                .practice_stats
+                .insert(&username, stats)
+                .expect("Failed to restore practice stats");
+        }
+        if let Some(ghost) = snapshot.practice_ghost {
+            self.state
+                .practice_ghosts
+                .insert(&username, ghost)
+                .expect("Failed to restore practice ghost");
+        }
+        if !snapshot.achievements.is_empty() {
+            self.state
+                .achievements
+                .insert(&username, snapshot.achievements)
+                .expect("Failed to restore achievements");
+        }
+        if !snapshot.friends.is_empty() {
+            self.state
+                .friendships
+                .insert(&username, snapshot.friends)
+                .expect("Failed to restore friendships");
+        }
+        if let Some(reset) = snapshot.credential_reset {
+            self.state
+                .credential_resets
+                .insert(&username, reset)
+                .expect("Failed to restore credential reset");
+        }
+        if let Some(attempts) = snapshot.login_attempts {
+            self.state
+                .login_attempts
+                .insert(&username, attempts)
+                .expect("Failed to restore login attempts");
+        }
+        if snapshot.tournaments_entered > 0 {
+            self.state
+                .tournaments_entered
+                .insert(&username, snapshot.tournaments_entered)
+                .expect("Failed to restore tournaments entered");
+        }
+        if !snapshot.hidden_tournament_results.is_empty() {
+            self.state
+                .hidden_tournament_results
+                .insert(&username, snapshot.hidden_tournament_results)
+                .expect("Failed to restore hidden tournament results");
+        }
+        self.index_username(&username);
+        self.state
+            .users
+            .insert(&username, snapshot.user)
+            .expect("Failed to restore user");
+    }
+
+    // Restored tournaments are published again for the replicas following the new chain
+    fn restore_tournament(&mut self, snapshot: TournamentSnapshot) {
+        let tournament_id = snapshot.tournament.id.clone();
+        for (username, ghost) in snapshot.ghosts {
+            self.state
+                .tournament_ghosts
+                .insert(&(tournament_id.clone(), username), ghost)
+                .expect("Failed to restore tournament ghost");
+        }
+        self.save_tournament(snapshot.tournament);
+        if let Some(leaderboard) = snapshot.leaderboard {
+            self.state
+                .tournament_leaderboards
+                .insert(&tournament_id, leaderboard.clone())
+                .expect("Failed to restore tournament leaderboard");
+            self.emit_event(FlappyEvent::TournamentLeaderboardReset {
+                tournament_id,
+                leaderboard,
+            });
+        }
+    }
+
+    fn restore_boards(&mut self, boards: BoardsSnapshot) {
+        self.state
+            .global_practice_stats
+            .set(boards.global_practice_stats);
+        self.state.pinned_tournaments.set(boards.pinned_tournaments);
+        self.state.tournament_counter.set(boards.tournament_counter);
+        self.state.challenge_counter.set(boards.challenge_counter);
+        self.state
+            .pending_score_counter
+            .set(boards.pending_score_counter);
+        self.state.reserved_usernames.set(boards.reserved_usernames);
+        self.state
+            .moderation_thresholds
+            .set(boards.moderation_thresholds);
+        self.state
+            .practice_leaderboard
+            .set(boards.practice_leaderboard.clone());
+        self.emit_event(FlappyEvent::PracticeLeaderboardReset {
+            leaderboard: boards.practice_leaderboard,
+        });
+    }
+
+    // Hands over to the new leaderboard chain: player chains follow it through the event,
+    // and messages held during the move are passed on after the state they apply to
+    async fn retire_leaderboard_chain(&mut self, mut leaderboard_move: LeaderboardMove) {
+        let new_chain_id = leaderboard_move.new_chain_id;
+        leaderboard_move.step = LeaderboardMoveStep::Completed;
+        self.state.leaderboard_move.set(Some(leaderboard_move));
+        self.state.is_leaderboard_chain.set(false);
+        self.state.leaderboard_chain_id.set(Some(new_chain_id));
+
+        self.emit_event(FlappyEvent::LeaderboardChainMoved {
+            leaderboard_chain_id: new_chain_id,
+        });
+
+        let held = self
+            .state
+            .held_messages
+            .elements()
+            .await
+            .expect("Failed to get held messages");
+        for message in held {
+            self.runtime.prepare_message(message).send_to(new_chain_id);
+        }
+        self.state.held_messages.clear();
+    }
+
+    // Switches to a new leaderboard chain, replacing the replica subscription of the old one
+    fn follow_leaderboard_chain(&mut self, leaderboard_chain_id: ChainId) {
+        let Some(current) = *self.state.leaderboard_chain_id.get() else {
+            self.configure_leaderboard_chain(leaderboard_chain_id);
+            return;
+        };
+        if current == leaderboard_chain_id {
+            return;
+        }
+
+        if current != self.runtime.chain_id() {
+            let application_id = self.runtime.application_id().forget_abi();
+            self.runtime.unsubscribe_from_events(
+                current,
+                application_id,
+                StreamName::from(FLAPPY_STREAM_NAME),
+            );
+        }
+        self.configure_leaderboard_chain(leaderboard_chain_id);
+    }
+
+    async fn check_score_achievements(
         &mut self,
-        tournament_id: String,
-        username: String,
+        username: &str,
         score: u64,
-        player_chain_id: ChainId,
+        tournament_id: Option<&str>,
     ) {
-        // Get tournament and validate it's active
-        let tournament = match self.state.tournaments.get(&tournament_id).await {
-            Ok(Some(tournament)) => tournament,
-            _ => return, // Tournament not found
-        };
+        self.unlock_achievement(username, AchievementKind::FirstRun, tournament_id)
+            .await;
+        for kind in score_achievements(score) {
+            self.unlock_achievement(username, kind, tournament_id).await;
+        }
+    }
 
-        if tournament.status != TournamentStatus::Active {
-            return; // Tournament not active
+    async fn record_tournament_entry(&mut self, username: &str) {
+        let entered = self
+            .state
+            .tournaments_entered
+            .get(username)
+            .await
+            .expect("Failed to get tournament entries")
+            .unwrap_or(0)
+            + 1;
+        self.state
+            .tournaments_entered
+            .insert(username, entered)
+            .expect("Failed to update tournament entries");
+
+        if entered >= TOURNAMENTS_ENTERED_MILESTONE {
+            self.unlock_achievement(username, AchievementKind::TenTournaments, None)
+                .await;
         }
+    }
 
-        if !tournament.participants.contains(&username) {
-            return; // User not registered for this tournament
+    async fn finalize_tournament_achievements(&mut self, tournament: &Tournament) {
+        for result in &tournament.results {
+            if result.rank <= 3 {
+                self.unlock_achievement(
+                    &result.username,
+                    AchievementKind::TournamentPodium,
+                    Some(&tournament.id),
+                )
+                .await;
+            }
+
+            if result.rank == 1 {
+                let (current_streak, _) = self.tournament_win_streaks(&result.username).await;
+                if current_streak >= WIN_STREAK_MILESTONE {
+                    self.unlock_achievement(
+                        &result.username,
+                        AchievementKind::WinStreak3,
+                        Some(&tournament.id),
+                    )
+                    .await;
+                }
+            }
         }
+    }
 
-        // Update live tournament leaderboard directly
-        self.update_tournament_leaderboard_with_score(
-            &tournament_id,
-            username,
-            score,
-            player_chain_id,
-        )
-        .await;
+    async fn tournament_win_streaks(&self, username: &str) -> (u64, u64) {
+        let tournament_ids = self
+            .state
+            .tournaments
+            .indices()
+            .await
+            .expect("Failed to get tournament IDs");
+
+        // (end_time, won) for every ended tournament the player placed in
+        let mut placements = Vec::new();
+        for tournament_id in tournament_ids {
+            if let Ok(Some(tournament)) = self.state.tournaments.get(&tournament_id).await {
+                if tournament.status != TournamentStatus::Ended {
+                    continue;
+                }
+                if let Some(result) = tournament.results.iter().find(|r| r.username == username) {
+                    placements.push((tournament.end_time.unwrap_or(0), result.rank == 1));
+                }
+            }
+        }
+        placements.sort_by_key(|(end_time, _)| *end_time);
+
+        let wins: Vec<bool> = placements.into_iter().map(|(_, won)| won).collect();
+        win_streaks(&wins)
     }
 
     async fn update_tournament_leaderboard_with_score(
@@ -781,7 +3810,7 @@ impl FlappyContract {
         username: String,
         new_score: u64,
         player_chain_id: ChainId,
-    ) {
+    ) -> bool {
         // Get current leaderboard
         let mut leaderboard = match self.state.tournament_leaderboards.get(tournament_id).await {
             Ok(Some(lb)) => lb,
@@ -793,6 +3822,11 @@ impl FlappyContract {
             .iter_mut()
             .find(|entry| entry.username == username);
 
+        let is_new_best = match &existing_entry {
+            Some(entry) => new_score > entry.score,
+            None => true,
+        };
+
         if let Some(entry) = existing_entry {
             // Update score if it's better
             if new_score > entry.score {
@@ -803,7 +3837,7 @@ impl FlappyContract {
         } else {
             // Add new entry for this user
             leaderboard.push(TournamentResult {
-                username,
+                username: username.clone(),
                 score: new_score,
                 rank: 0, // Will be set after sorting
                 chain_id: player_chain_id,
@@ -811,19 +3845,599 @@ impl FlappyContract {
             });
         }
 
-        // Sort by score descending (highest first)
-        leaderboard.sort_by(|a, b| b.score.cmp(&a.score));
+        rank_tournament_results(&mut leaderboard);
 
-        // Assign ranks
-        for (index, result) in leaderboard.iter_mut().enumerate() {
-            result.rank = (index + 1) as u32;
-        }
+        let updated_result = if is_new_best {
+            leaderboard
+                .iter()
+                .find(|entry| entry.username == username)
+                .cloned()
+        } else {
+            None
+        };
 
         // Store the updated leaderboard
         self.state
             .tournament_leaderboards
             .insert(tournament_id, leaderboard)
             .expect("Failed to update tournament leaderboard");
+
+        if let Some(result) = updated_result {
+            self.emit_event(FlappyEvent::TournamentScoreUpdated {
+                tournament_id: tournament_id.to_string(),
+                result,
+            });
+        }
+
+        is_new_best
+    }
+
+    // Checks a client hash against the stored credential, upgrading legacy plain hashes
+    async fn verify_credential(&mut self, user: &mut User, client_hash: &str) -> bool {
+        if let Some(credential) = self
+            .state
+            .credentials
+            .get(&user.username)
+            .await
+            .expect("Failed to get credential")
+        {
+            return credential.verify(client_hash);
+        }
+
+        // Users registered before salted credentials stored the client hash verbatim
+        if user.hash.is_empty() || user.hash != client_hash {
+            return false;
+        }
+
+        self.set_credential(user, client_hash);
+        self.state
+            .users
+            .insert(&user.username, user.clone())
+            .expect("Failed to update user");
+        true
+    }
+
+    // Stores a freshly salted credential; callers persist the user afterwards
+    fn set_credential(&mut self, user: &mut User, client_hash: &str) {
+        let seed = format!(
+            "{}:{}:{}:{}",
+            self.runtime.chain_id(),
+            self.runtime.block_height(),
+            self.runtime.system_time().micros(),
+            user.username
+        );
+        let credential = Credential::new(derive_credential_salt(&seed), client_hash);
+
+        self.state
+            .credentials
+            .insert(&user.username, credential)
+            .expect("Failed to store credential");
+        user.hash.clear();
+    }
+
+    // Logs a user in or registers them, storing the result for the chain the request came from
+    async fn login_or_register(
+        &mut self,
+        username: String,
+        hash: String,
+        requester_chain_id: ChainId,
+    ) {
+        // Failures from other chains don't lock the owner out of their own chain
+        let own_chain = Some(requester_chain_id) == self.user_chain_id(&username).await;
+
+        // Refuse attempts while the username or the requesting chain is locked out
+        let now = self.runtime.system_time().micros();
+        if let Some(locked_until) = self
+            .login_locked_until(&username, requester_chain_id, own_chain, now)
+            .await
+        {
+            let result = LoginResult {
+                success: false,
+                user: None,
+                message: format!(
+                    "Too many failed login attempts, try again in {} seconds",
+                    (locked_until - now).div_ceil(1_000_000)
+                ),
+                is_new_user: false,
+            };
+            self.state
+                .login_results
+                .insert(&requester_chain_id, result)
+                .expect("Failed to store login result");
+            return;
+        }
+
+        // Banned, suspended and deleted accounts can't log in
+        if let Some(restriction) = self.active_restriction(&username).await {
+            let message = match restriction.kind {
+                RestrictionKind::Suspended => format!(
+                    "Account suspended for another {} seconds: {}",
+                    restriction
+                        .expires_at
+                        .unwrap_or(now)
+                        .saturating_sub(now)
+                        .div_ceil(1_000_000),
+                    restriction.reason
+                ),
+                RestrictionKind::Banned => {
+                    format!("Account banned: {}", restriction.reason)
+                }
+                RestrictionKind::Deleted => "Account has been deleted".to_string(),
+            };
+            let result = LoginResult {
+                success: false,
+                user: None,
+                message,
+                is_new_user: false,
+            };
+            self.state
+                .login_results
+                .insert(&requester_chain_id, result)
+                .expect("Failed to store login result");
+            return;
+        }
+
+        let result = match self.state.users.get(&username).await {
+            Ok(Some(mut existing_user)) => {
+                // User exists - check password
+                if self.verify_credential(&mut existing_user, &hash).await {
+                    // Login successful
+                    self.state
+                        .login_attempts
+                        .remove(&username)
+                        .expect("Failed to clear login attempts");
+                    LoginResult {
+                        success: true,
+                        user: Some(existing_user),
+                        message: "Login successful".to_string(),
+                        is_new_user: false,
+                    }
+                } else {
+                    // Wrong password
+                    self.record_login_failure(&username, requester_chain_id, own_chain)
+                        .await;
+                    LoginResult {
+                        success: false,
+                        user: None,
+                        message: "Invalid password".to_string(),
+                        is_new_user: false,
+                    }
+                }
+            }
+            _ => {
+                // User doesn't exist - validate the name and register new user
+                if let Err(message) = self.validate_new_username(&username).await {
+                    LoginResult {
+                        success: false,
+                        user: None,
+                        message,
+                        is_new_user: false,
+                    }
+                } else {
+                    let mut new_user = User {
+                        username: username.clone(),
+                        hash: String::new(),
+                        role: UserRole::Player, // Default role
+                        created_at: self.runtime.system_time().micros(),
+                        chain_id: Some(requester_chain_id),
+                    };
+                    self.set_credential(&mut new_user, &hash);
+
+                    match self.state.users.insert(&username, new_user.clone()) {
+                        Ok(_) => {
+                            self.index_username(&username);
+                            self.emit_event(FlappyEvent::UserRegistered {
+                                user: UserProfile::from(&new_user),
+                            });
+                            LoginResult {
+                                success: true,
+                                user: Some(new_user),
+                                message: "User registered successfully".to_string(),
+                                is_new_user: true,
+                            }
+                        }
+                        Err(_) => LoginResult {
+                            success: false,
+                            user: None,
+                            message: "Failed to register user".to_string(),
+                            is_new_user: false,
+                        },
+                    }
+                }
+            }
+        };
+
+        // Store result for the specific requesting chain
+        self.state
+            .login_results
+            .insert(&requester_chain_id, result)
+            .expect("Failed to store login result");
+    }
+
+    // End of the longest active lockout for the username or the requesting chain. The username
+    // lockout doesn't apply on the user's own chain, which only its chain lockout limits
+    async fn login_locked_until(
+        &self,
+        username: &str,
+        chain_id: ChainId,
+        own_chain: bool,
+        now: u64,
+    ) -> Option<u64> {
+        let mut attempts = Vec::new();
+        if !own_chain {
+            let user_attempts = self
+                .state
+                .login_attempts
+                .get(username)
+                .await
+                .expect("Failed to get login attempts")
+                .unwrap_or_default();
+            attempts.push(user_attempts);
+        }
+        let chain_attempts = self
+            .state
+            .chain_login_attempts
+            .get(&chain_id)
+            .await
+            .expect("Failed to get login attempts")
+            .unwrap_or_default();
+        attempts.push(chain_attempts);
+
+        attempts
+            .iter()
+            .filter(|attempts| attempts.is_locked(now))
+            .map(|attempts| attempts.locked_until)
+            .max()
+    }
+
+    async fn record_login_failure(&mut self, username: &str, chain_id: ChainId, own_chain: bool) {
+        let now = self.runtime.system_time().micros();
+
+        if !own_chain {
+            let mut user_attempts = self
+                .state
+                .login_attempts
+                .get(username)
+                .await
+                .expect("Failed to get login attempts")
+                .unwrap_or_default();
+            user_attempts.record_failure(now);
+            self.state
+                .login_attempts
+                .insert(username, user_attempts)
+                .expect("Failed to update login attempts");
+        }
+
+        let mut chain_attempts = self
+            .state
+            .chain_login_attempts
+            .get(&chain_id)
+            .await
+            .expect("Failed to get login attempts")
+            .unwrap_or_default();
+        chain_attempts.record_failure(now);
+        self.state
+            .chain_login_attempts
+            .insert(&chain_id, chain_attempts)
+            .expect("Failed to update login attempts");
+    }
+
+    // Returns the account's restriction while it applies, dropping suspensions that ran out
+    async fn active_restriction(&mut self, username: &str) -> Option<AccountRestriction> {
+        let restriction = self
+            .state
+            .account_restrictions
+            .get(username)
+            .await
+            .expect("Failed to get account restriction")?;
+
+        if restriction.is_active(self.runtime.system_time().micros()) {
+            return Some(restriction);
+        }
+
+        self.state
+            .account_restrictions
+            .remove(username)
+            .expect("Failed to remove account restriction");
+        if restriction.scores_hidden {
+            self.reset_practice_leaderboard().await;
+            self.restore_tournament_results(username).await;
+        }
+        None
+    }
+
+    async fn is_deleted(&self, username: &str) -> bool {
+        matches!(
+            self.state.account_restrictions.get(username).await,
+            Ok(Some(AccountRestriction {
+                kind: RestrictionKind::Deleted,
+                ..
+            }))
+        )
+    }
+
+    // Users whose scores are currently left out of the leaderboards
+    async fn hidden_score_usernames(&mut self) -> Vec<String> {
+        let now = self.runtime.system_time().micros();
+        let usernames = self
+            .state
+            .account_restrictions
+            .indices()
+            .await
+            .expect("Failed to get restricted usernames");
+
+        let mut hidden = Vec::new();
+        for username in usernames {
+            if let Ok(Some(restriction)) = self.state.account_restrictions.get(&username).await {
+                if restriction.hides_scores(now) {
+                    hidden.push(username);
+                }
+            }
+        }
+        hidden
+    }
+
+    // Removes credentials and sessions but keeps the user record so the name stays taken
+    async fn soft_delete_user(&mut self, username: &str, deleted_by: &str, purge_scores: bool) {
+        if self.is_deleted(username).await {
+            panic!("User '{}' has already been deleted", username);
+        }
+
+        self.state
+            .credentials
+            .remove(username)
+            .expect("Failed to delete credential");
+        self.state
+            .credential_resets
+            .remove(username)
+            .expect("Failed to delete credential reset");
+        self.state
+            .login_attempts
+            .remove(username)
+            .expect("Failed to delete login attempts");
+
+        let restriction = AccountRestriction {
+            kind: RestrictionKind::Deleted,
+            reason: "Account deleted".to_string(),
+            issued_by: deleted_by.to_string(),
+            issued_at: self.runtime.system_time().micros(),
+            expires_at: None,
+            scores_hidden: true,
+        };
+        self.state
+            .account_restrictions
+            .insert(username, restriction)
+            .expect("Failed to store account restriction");
+
+        // Clean up user's login results for all chains if any exist
+        self.clear_login_sessions(username).await;
+
+        if purge_scores {
+            self.purge_user_scores(username).await;
+        } else {
+            self.hide_tournament_results(username).await;
+        }
+        self.reset_practice_leaderboard().await;
+    }
+
+    // Takes the user's entries off the live tournament boards, keeping them to restore later
+    async fn hide_tournament_results(&mut self, username: &str) {
+        let mut hidden = self
+            .state
+            .hidden_tournament_results
+            .get(username)
+            .await
+            .expect("Failed to get hidden tournament results")
+            .unwrap_or_default();
+
+        let tournament_ids = self
+            .state
+            .tournament_leaderboards
+            .indices()
+            .await
+            .expect("Failed to get tournament IDs");
+        for tournament_id in tournament_ids {
+            let Some(mut leaderboard) = self
+                .state
+                .tournament_leaderboards
+                .get(&tournament_id)
+                .await
+                .expect("Failed to get tournament leaderboard")
+            else {
+                continue;
+            };
+            let Some(position) = leaderboard.iter().position(|r| r.username == username) else {
+                continue;
+            };
+
+            hidden.push((tournament_id.clone(), leaderboard.remove(position)));
+            rank_tournament_results(&mut leaderboard);
+            self.reset_tournament_leaderboard(tournament_id, leaderboard);
+        }
+
+        if !hidden.is_empty() {
+            self.state
+                .hidden_tournament_results
+                .insert(username, hidden)
+                .expect("Failed to store hidden tournament results");
+        }
+    }
+
+    // Puts hidden entries back on the live tournament boards once the user's scores show again
+    async fn restore_tournament_results(&mut self, username: &str) {
+        let Some(hidden) = self
+            .state
+            .hidden_tournament_results
+            .get(username)
+            .await
+            .expect("Failed to get hidden tournament results")
+        else {
+            return;
+        };
+        self.state
+            .hidden_tournament_results
+            .remove(username)
+            .expect("Failed to remove hidden tournament results");
+
+        for (tournament_id, result) in hidden {
+            let mut leaderboard = self
+                .state
+                .tournament_leaderboards
+                .get(&tournament_id)
+                .await
+                .expect("Failed to get tournament leaderboard")
+                .unwrap_or_default();
+            if leaderboard
+                .iter()
+                .any(|r| r.username == username && r.score >= result.score)
+            {
+                continue;
+            }
+
+            leaderboard.retain(|r| r.username != username);
+            leaderboard.push(result);
+            rank_tournament_results(&mut leaderboard);
+            self.reset_tournament_leaderboard(tournament_id, leaderboard);
+        }
+    }
+
+    // Stores a tournament board and publishes it whole for player chain replicas
+    fn reset_tournament_leaderboard(
+        &mut self,
+        tournament_id: String,
+        leaderboard: Vec<TournamentResult>,
+    ) {
+        self.state
+            .tournament_leaderboards
+            .insert(&tournament_id, leaderboard.clone())
+            .expect("Failed to update tournament leaderboard");
+        self.emit_event(FlappyEvent::TournamentLeaderboardReset {
+            tournament_id,
+            leaderboard,
+        });
+    }
+
+    // Permanently removes a user's practice and tournament scores
+    async fn purge_user_scores(&mut self, username: &str) {
+        self.state
+            .practice_best_scores
+            .remove(username)
+            .expect("Failed to remove practice best score");
+        self.state
+            .hidden_tournament_results
+            .remove(username)
+            .expect("Failed to remove hidden tournament results");
+        self.state
+            .practice_ghosts
+            .remove(username)
+            .expect("Failed to remove ghost run");
+
+        let tournament_ids = self
+            .state
+            .tournaments
+            .indices()
+            .await
+            .expect("Failed to get tournament IDs");
+        for tournament_id in tournament_ids {
+            if let Ok(Some(mut leaderboard)) =
+                self.state.tournament_leaderboards.get(&tournament_id).await
+            {
+                if leaderboard.iter().any(|r| r.username == username) {
+                    leaderboard.retain(|r| r.username != username);
+                    rank_tournament_results(&mut leaderboard);
+                    self.state
+                        .tournament_leaderboards
+                        .insert(&tournament_id, leaderboard.clone())
+                        .expect("Failed to update tournament leaderboard");
+                    self.emit_event(FlappyEvent::TournamentLeaderboardReset {
+                        tournament_id: tournament_id.clone(),
+                        leaderboard,
+                    });
+                }
+            }
+
+            if let Ok(Some(mut tournament)) = self.state.tournaments.get(&tournament_id).await {
+                if tournament.results.iter().any(|r| r.username == username) {
+                    tournament.results.retain(|r| r.username != username);
+                    rank_tournament_results(&mut tournament.results);
+                    self.save_tournament(tournament);
+                }
+            }
+
+            self.state
+                .tournament_ghosts
+                .remove(&(tournament_id, username.to_string()))
+                .expect("Failed to remove ghost run");
+        }
+    }
+
+    async fn clear_login_sessions(&mut self, username: &str) {
+        let chain_ids = self
+            .state
+            .login_results
+            .indices()
+            .await
+            .expect("Failed to get login result chain IDs");
+
+        for chain_id in chain_ids {
+            if let Ok(Some(login_result)) = self.state.login_results.get(&chain_id).await {
+                if let Some(result_user) = &login_result.user {
+                    if result_user.username == username {
+                        self.state
+                            .login_results
+                            .remove(&chain_id)
+                            .expect("Failed to remove login result");
+                    }
+                }
+            }
+        }
+    }
+
+    async fn validate_new_username(&self, username: &str) -> Result<(), String> {
+        validate_username(username)?;
+
+        let normalized = normalize_username(username);
+        if self
+            .state
+            .reserved_usernames
+            .get()
+            .iter()
+            .any(|reserved| normalize_username(reserved) == normalized)
+        {
+            return Err(format!("Username '{}' is reserved", username));
+        }
+
+        // Names differing only in case count as taken
+        if self
+            .state
+            .normalized_usernames
+            .contains_key(&username.to_ascii_lowercase())
+            .await
+            .expect("Failed to check usernames")
+        {
+            return Err(format!("Username '{}' is already taken", username));
+        }
+
+        Ok(())
+    }
+
+    // Indexes a registered username for the case-insensitive lookup
+    fn index_username(&mut self, username: &str) {
+        self.state
+            .normalized_usernames
+            .insert(&username.to_ascii_lowercase(), username.to_string())
+            .expect("Failed to index username");
+    }
+
+    // The signer, if it's one of the configured admin keys. The leaderboard chain's node
+    // signs players' operations with its owner key, so chain ownership alone isn't enough
+    fn admin_signer(&mut self) -> Option<AccountOwner> {
+        let signer = self.runtime.authenticated_signer()?;
+        self.runtime
+            .application_parameters()
+            .admin_owners
+            .contains(&signer)
+            .then_some(signer)
     }
 
     async fn validate_admin_session(&self, caller_chain_id: ChainId) -> Result<User, String> {
@@ -891,9 +4505,12 @@ impl FlappyContract {
                             tournament.end_time = Some(current_time);
 
                             // Generate final results from current leaderboard
-                            if let Ok(Some(leaderboard)) =
+                            if let Ok(Some(mut leaderboard)) =
                                 self.state.tournament_leaderboards.get(&tournament_id).await
                             {
+                                let hidden_usernames = self.hidden_score_usernames().await;
+                                leaderboard.retain(|r| !hidden_usernames.contains(&r.username));
+                                rank_tournament_results(&mut leaderboard);
                                 tournament.results = leaderboard;
                             }
 
@@ -909,10 +4526,17 @@ impl FlappyContract {
                 }
 
                 if updated {
-                    self.state
-                        .tournaments
-                        .insert(&tournament_id, tournament)
-                        .expect("Failed to update tournament status");
+                    let ended = tournament.status == TournamentStatus::Ended;
+                    self.save_tournament(tournament.clone());
+
+                    self.emit_event(FlappyEvent::TournamentStatusChanged {
+                        tournament_id: tournament_id.clone(),
+                        status: tournament.status,
+                    });
+
+                    if ended {
+                        self.finalize_tournament_achievements(&tournament).await;
+                    }
                 }
             }
         }
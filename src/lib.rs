@@ -1,9 +1,10 @@
 use async_graphql::{Request, Response};
 use linera_sdk::{
     graphql::GraphQLMutationRoot,
-    linera_base_types::{ChainId, ContractAbi, ServiceAbi},
+    linera_base_types::{AccountOwner, ChainId, ContractAbi, ServiceAbi},
 };
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 pub struct FlappyAbi;
 
@@ -17,10 +18,63 @@ impl ServiceAbi for FlappyAbi {
     type QueryResponse = Response;
 }
 
-// Application parameters - can be set after deployment
+// Application parameters, fixed when the application is created
 #[derive(Debug, Clone, Deserialize, Serialize, Default)]
 pub struct ApplicationParameters {
-    pub leaderboard_chain_id: Option<ChainId>,
+    pub leaderboard_chain_id: Option<ChainId>, // Chains configure themselves from it, no SetupGame needed
+    #[serde(default)]
+    pub game: GameConstants,
+    // Keys that may sign admin-only operations; the leaderboard chain's node signs
+    // players' operations with its owner key, so owning the chain proves nothing
+    #[serde(default)]
+    pub admin_owners: Vec<AccountOwner>,
+}
+
+// Game physics scores are validated against, matching GAME_CONFIG in the frontend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, async_graphql::SimpleObject)]
+#[serde(default)]
+pub struct GameConstants {
+    pub gravity: u64,      // Bird acceleration in thousandths of a pixel per frame squared
+    pub pipe_gap: u64,     // Pixels between the top and bottom pipe
+    pub pipe_speed: u64,   // Pipe speed in thousandths of a pixel per frame
+    pub pipe_spacing: u64, // Pixels between consecutive pipes
+    pub fall_height: u64,  // Pixels between the bird's start position and the ground
+}
+
+impl Default for GameConstants {
+    fn default() -> Self {
+        GameConstants {
+            gravity: 300,
+            pipe_gap: 140,
+            pipe_speed: 1_500,
+            pipe_spacing: 180,
+            fall_height: 120,
+        }
+    }
+}
+
+pub const FRAMES_PER_SECOND: u64 = 60;
+
+impl GameConstants {
+    // Whether runs take time under these constants: the bird takes at least one frame to reach
+    // the ground and each pipe at least one frame to pass
+    pub fn is_playable(&self) -> bool {
+        self.gravity > 0
+            && self.fall_height > 0
+            && self.pipe_gap > 0
+            && self.pipe_speed > 0
+            && self.pipe_spacing.saturating_mul(1_000) >= self.pipe_speed
+            && self.min_run_duration(0) > 0
+    }
+
+    // Shortest time in microseconds a run scoring `score` can take: falling straight to the
+    // ground (ignoring the fall speed cap keeps this a lower bound) plus one pipe per point
+    pub fn min_run_duration(&self, score: u64) -> u64 {
+        let fall_frames = (self.fall_height.saturating_mul(2_000) / self.gravity.max(1)).isqrt();
+        let pipe_frames = self.pipe_spacing.saturating_mul(1_000) / self.pipe_speed.max(1);
+        let frames = fall_frames.saturating_add(score.saturating_mul(pipe_frames));
+        frames.saturating_mul(1_000_000) / FRAMES_PER_SECOND
+    }
 }
 
 // User management structures
@@ -30,23 +84,236 @@ pub enum UserRole {
     Player,
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+// Stored user record, never returned from queries directly (see UserProfile)
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct User {
     pub username: String,
-    pub hash: String, // SHA-256 hash of username+password
+    pub hash: String, // Legacy client hash, empty once moved to a salted Credential
     pub role: UserRole,
     pub created_at: u64, // timestamp
     pub chain_id: Option<ChainId>, // user's personal chain
 }
 
+// Public view of a user, free of credential material
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct UserProfile {
+    pub username: String,
+    pub role: UserRole,
+    pub created_at: u64, // timestamp
+    pub chain_id: Option<ChainId>, // user's personal chain
+}
+
+impl From<&User> for UserProfile {
+    fn from(user: &User) -> Self {
+        UserProfile {
+            username: user.username.clone(),
+            role: user.role,
+            created_at: user.created_at,
+            chain_id: user.chain_id,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, async_graphql::SimpleObject)]
+#[graphql(complex)]
 pub struct LoginResult {
     pub success: bool,
+    #[graphql(skip)]
     pub user: Option<User>,
     pub message: String,
     pub is_new_user: bool,
 }
 
+#[async_graphql::ComplexObject]
+impl LoginResult {
+    async fn user(&self) -> Option<UserProfile> {
+        self.user.as_ref().map(UserProfile::from)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CredentialReset {
+    pub token_hash: String, // SHA-256 hex of the one-time token
+    pub issued_by: String, // Admin key that issued it
+    pub issued_at: u64, // timestamp
+    pub expires_at: u64, // timestamp
+}
+
+// Current scheme for salted credential hashes
+pub const CREDENTIAL_HASH_VERSION: u32 = 1;
+
+// Salted hash of the client-side credential hash, stored per user on the leaderboard chain
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct Credential {
+    pub version: u32,
+    pub salt: String, // hex
+    pub hash: String, // hex
+}
+
+impl Credential {
+    pub fn new(salt: String, client_hash: &str) -> Self {
+        let hash = salted_hash(CREDENTIAL_HASH_VERSION, &salt, client_hash)
+            .expect("Failed to hash with the current credential version");
+        Credential {
+            version: CREDENTIAL_HASH_VERSION,
+            salt,
+            hash,
+        }
+    }
+
+    // Credentials of an unknown version never match
+    pub fn verify(&self, client_hash: &str) -> bool {
+        salted_hash(self.version, &self.salt, client_hash).is_some_and(|hash| hash == self.hash)
+    }
+}
+
+fn salted_hash(version: u32, salt: &str, client_hash: &str) -> Option<String> {
+    match version {
+        1 => Some(sha256_hex(
+            format!("v1:{}:{}", salt, client_hash).as_bytes(),
+        )),
+        _ => None,
+    }
+}
+
+// Salt derived from chain data unique to the registering block and user
+pub fn derive_credential_salt(seed: &str) -> String {
+    sha256_hex(format!("flappy-salt:{}", seed).as_bytes())
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+// How long a credential reset token stays valid (24 hours in microseconds)
+pub const CREDENTIAL_RESET_TTL: u64 = 24 * 60 * 60 * 1_000_000;
+
+// SHA-256 of a reset token as lowercase hex
+pub fn hash_reset_token(token: &str) -> String {
+    sha256_hex(token.as_bytes())
+}
+
+// Failed logins allowed before lockouts start
+pub const LOGIN_FREE_ATTEMPTS: u32 = 3;
+
+// First lockout (30 seconds in microseconds), doubled on every further failure
+pub const LOGIN_BASE_LOCKOUT: u64 = 30 * 1_000_000;
+pub const LOGIN_MAX_LOCKOUT: u64 = 24 * 60 * 60 * 1_000_000;
+
+// Failures older than this are forgotten (24 hours in microseconds)
+pub const LOGIN_FAILURE_WINDOW: u64 = 24 * 60 * 60 * 1_000_000;
+
+// Failed login attempts tracked per username and per requester chain
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct LoginAttempts {
+    pub failures: u32,
+    pub last_failure_at: u64, // timestamp
+    pub locked_until: u64, // timestamp, 0 if never locked
+}
+
+impl LoginAttempts {
+    pub fn is_locked(&self, now: u64) -> bool {
+        now < self.locked_until
+    }
+
+    pub fn record_failure(&mut self, now: u64) {
+        if now.saturating_sub(self.last_failure_at) > LOGIN_FAILURE_WINDOW {
+            self.failures = 0;
+        }
+        self.failures += 1;
+        self.last_failure_at = now;
+
+        if self.failures >= LOGIN_FREE_ATTEMPTS {
+            let doublings = (self.failures - LOGIN_FREE_ATTEMPTS).min(32);
+            let lockout = LOGIN_BASE_LOCKOUT
+                .saturating_mul(1 << doublings)
+                .min(LOGIN_MAX_LOCKOUT);
+            self.locked_until = now + lockout;
+        }
+    }
+}
+
+// Account moderation structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
+pub enum RestrictionKind {
+    Suspended, // Temporary, lifted at expires_at
+    Banned,    // Permanent until an admin lifts it
+    Deleted,   // Soft-deleted, the username stays taken
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct AccountRestriction {
+    pub kind: RestrictionKind,
+    pub reason: String,
+    pub issued_by: String, // Admin who issued it
+    pub issued_at: u64, // timestamp
+    pub expires_at: Option<u64>, // timestamp, None for bans and deletions
+    pub scores_hidden: bool, // Scores are left out of all leaderboards while active
+}
+
+impl AccountRestriction {
+    pub fn is_active(&self, now: u64) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+
+    pub fn hides_scores(&self, now: u64) -> bool {
+        self.scores_hidden && self.is_active(now)
+    }
+}
+
+// Username rules applied when registering
+pub const MIN_USERNAME_LENGTH: usize = 3;
+pub const MAX_USERNAME_LENGTH: usize = 20;
+
+// Names nobody can register, seeded into the editable reserved list at instantiation
+pub const DEFAULT_RESERVED_USERNAMES: [&str; 6] = [
+    "admin",
+    "administrator",
+    "moderator",
+    "leaderboard",
+    "system",
+    "root",
+];
+
+pub fn validate_username(username: &str) -> Result<(), String> {
+    let length = username.chars().count();
+    if !(MIN_USERNAME_LENGTH..=MAX_USERNAME_LENGTH).contains(&length) {
+        return Err(format!(
+            "Username must be between {} and {} characters",
+            MIN_USERNAME_LENGTH, MAX_USERNAME_LENGTH
+        ));
+    }
+
+    if !username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    {
+        return Err("Username may only contain letters, digits, '_' and '-'".to_string());
+    }
+
+    Ok(())
+}
+
+// Folds case, separators and lookalike digits so "Adm1n" and "ad_min" match "admin"
+pub fn normalize_username(username: &str) -> String {
+    username
+        .chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .map(|c| match c.to_ascii_lowercase() {
+            '0' => 'o',
+            '1' | 'i' => 'l',
+            '3' => 'e',
+            '4' => 'a',
+            '5' => 's',
+            '7' => 't',
+            c => c,
+        })
+        .collect()
+}
+
 // Instantiation argument for creating the application
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct InstantiationArgument {
@@ -55,81 +322,250 @@ pub struct InstantiationArgument {
     pub admin_hash: Option<String>,     // Only for leaderboard chain
 }
 
-#[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
-pub enum Operation {
-    // Setup operation to configure the game (only works once)
-    SetupGame {
-        leaderboard_chain_id: ChainId,
-        leaderboard_name: String,
-    },
-    // User management operations
-    LoginOrRegister {
+pub use operation::Operation;
+
+// `GraphQLMutationRoot` turns every operation field into a resolver argument,
+// more than clippy allows for `UpdateTournament`
+#[allow(clippy::too_many_arguments)]
+mod operation {
+    use super::*;
+
+    #[derive(Debug, Deserialize, Serialize, GraphQLMutationRoot)]
+    pub enum Operation {
+        // Sets the chain's player name; also configures the leaderboard chain for deployments
+        // created without it in the application parameters
+        SetupGame {
+            leaderboard_chain_id: ChainId,
+            leaderboard_name: String,
+        },
+        // User management operations
+        LoginOrRegister {
+            username: String,
+            hash: String,
+        },
+        DeleteUser {
+            caller_chain_id: ChainId,
+            username: String,
+            purge_scores: bool, // Permanently removes the user's scores instead of hiding them
+        },
+        DeleteMyAccount {
+            username: String,
+            hash: String, // The account's credential, checked like a login
+            purge_scores: bool, // Permanently removes the user's scores instead of hiding them
+        },
+        SetModerationThresholds {
+            caller_chain_id: ChainId,
+            thresholds: ModerationThresholds,
+        },
+        ApproveScore {
+            caller_chain_id: ChainId,
+            pending_id: String,
+        },
+        RejectScore {
+            caller_chain_id: ChainId,
+            pending_id: String,
+        },
+        BanUser {
+            caller_chain_id: ChainId,
+            username: String,
+            reason: String,
+            duration: Option<u64>, // Seconds to suspend for, None bans until lifted
+            hide_scores: bool, // Leave the user's scores out of all leaderboards
+        },
+        UnbanUser {
+            caller_chain_id: ChainId,
+            username: String,
+        },
+        ChangeCredential {
+            caller_chain_id: ChainId,
+            username: String,
+            old_hash: String,
+            new_hash: String,
+        },
+        // Signed with an admin key; resetting an admin takes two different admin keys
+        IssueCredentialReset {
+            username: String,
+            token_hash: String, // SHA-256 hex of the token handed to the user off-chain
+        },
+        ResetCredential {
+            username: String,
+            token: String, // One-time token, only its hash is stored
+            new_hash: String,
+        },
+        ReserveUsername {
+            caller_chain_id: ChainId,
+            username: String,
+            reserved: bool, // true to reserve, false to release
+        },
+        ClearLoginLockout {
+            caller_chain_id: ChainId,
+            username: Option<String>, // Clears failed logins for this username
+            chain_id: Option<ChainId>, // Clears failed logins from this requester chain
+        },
+        // Practice mode operations
+        StartRun, // Issues the run ticket the next practice or tournament score is submitted with
+        SubmitPracticeScore {
+            username: String,
+            score: u64,
+            tap_frames: Option<Vec<u32>>, // Frame indices of each tap, kept as a ghost run for bests
+            ticket_id: u64,
+        },
+        SetPracticeHistoryCap {
+            cap: u64, // Number of most recent runs kept in the personal history
+        },
+        FlushPracticeRuns {
+            username: String, // Send pending run statistics to the leaderboard chain now
+        },
+        // Friends operations (player chains)
+        AddFriend {
+            username: String,
+            friend_username: String,
+        },
+        AcceptFriend {
+            username: String,
+            friend_username: String, // User whose request is accepted
+        },
+        RemoveFriend {
+            username: String,
+            friend_username: String, // Also cancels or declines pending requests
+        },
+        // Challenge operations (player chains)
+        ChallengePlayer {
+            username: String,
+            opponent: String,
+            seed: u64, // Seed for the shared pipe course
+            deadline: u64, // Seconds; unsettled challenges are resolved after it
+        },
+        AcceptChallenge {
+            username: String,
+            challenge_id: String,
+        },
+        SubmitChallengeScore {
+            username: String,
+            challenge_id: String,
+            score: u64,
+        },
+        ResolveChallenge {
+            username: String, // A party to the challenge, unless sent by the leaderboard chain owner
+            challenge_id: String, // Settles a challenge past its deadline
+        },
+        // Tournament management operations
+        CreateTournament {
+            caller_chain_id: ChainId,
+            name: String,
+            description: String,
+            start_time: Option<u64>, // Optional scheduled start time
+            end_time: Option<u64>, // Optional scheduled end time
+        },
+        JoinTournament {
+            tournament_id: String,
+            username: String,
+        },
+        StartTournament {
+            caller_chain_id: ChainId,
+            tournament_id: String,
+        },
+        EndTournament {
+            caller_chain_id: ChainId,
+            tournament_id: String,
+            results: Vec<TournamentResult>,
+        },
+        PinTournament {
+            caller_chain_id: ChainId,
+            tournament_id: String,
+            pin: bool, // true to pin, false to unpin
+        },
+        UpdateTournament {
+            caller_chain_id: ChainId,
+            tournament_id: String,
+            name: Option<String>,
+            description: Option<String>,
+            start_time: Option<u64>,
+            end_time: Option<u64>,
+        },
+        DeleteTournament {
+            caller_chain_id: ChainId,
+            tournament_id: String,
+        },
+        // Leaderboard chain owner only; repeat to send the next batch of state until the move
+        // completes. The new chain must already run the application with this chain as its
+        // leaderboard chain, or it turns the move down
+        MoveLeaderboardChain {
+            new_leaderboard_chain_id: ChainId,
+        },
+        // Leaderboard chain owner only; stops a move that hasn't completed and handles the
+        // messages held during it here
+        CancelLeaderboardMove,
+        SubmitTournamentScore {
+            tournament_id: String,
+            username: String,
+            score: u64,
+            tap_frames: Option<Vec<u32>>, // Frame indices of each tap, kept as a ghost run for bests
+            ticket_id: u64,
+        },
+    }
+}
+
+// Add message types for cross-chain communication
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum FlappyMessage {
+    // Practice mode messages
+    UpdatePracticeBest {
         username: String,
-        hash: String,
-        requester_chain_id: ChainId, // Track which chain made the request
+        score: u64,
+        player_chain_id: ChainId,
+        ghost: Option<Vec<u8>>, // Encoded tap frames of the run
+        submitted_at: u64,      // Block time of the submission on the player chain
+        ticket: RunTicket,      // Ticket the run was started with
     },
-    DeleteUser {
-        caller_chain_id: ChainId,
+    PracticeRunsSummary {
         username: String,
+        summary: PracticeRunsSummary, // Runs played since the last flush
+        player_chain_id: ChainId,
     },
-    // Practice mode operations
-    SubmitPracticeScore {
+    // User management messages
+    LoginOrRegister {
         username: String,
-        score: u64,
+        hash: String, // Result is stored for the sending chain
     },
-    // Tournament management operations
-    CreateTournament {
-        caller_chain_id: ChainId,
-        name: String,
-        description: String,
-        start_time: Option<u64>, // Optional scheduled start time
-        end_time: Option<u64>, // Optional scheduled end time
+    LoginCompleted {
+        result: LoginResult, // Leaderboard chain -> the chain that logged in
     },
-    JoinTournament {
-        tournament_id: String,
-        username: String,
+    // Friends messages, routed through the leaderboard chain
+    FriendRequest {
+        from_username: String,
+        to_username: String,
     },
-    StartTournament {
-        caller_chain_id: ChainId,
-        tournament_id: String,
+    FriendAccepted {
+        username: String,
+        friend_username: String, // User whose request was accepted
     },
-    EndTournament {
-        caller_chain_id: ChainId,
-        tournament_id: String,
-        results: Vec<TournamentResult>,
+    FriendRemoved {
+        username: String,
+        friend_username: String,
     },
-    PinTournament {
-        caller_chain_id: ChainId,
-        tournament_id: String,
-        pin: bool, // true to pin, false to unpin
+    // Challenge messages
+    CreateChallenge {
+        challenger: String,
+        opponent: String,
+        seed: u64,
+        deadline: u64, // timestamp
     },
-    UpdateTournament {
-        caller_chain_id: ChainId,
-        tournament_id: String,
-        name: Option<String>,
-        description: Option<String>,
-        start_time: Option<u64>,
-        end_time: Option<u64>,
-    },
-    DeleteTournament {
-        caller_chain_id: ChainId,
-        tournament_id: String,
+    AcceptChallenge {
+        username: String,
+        challenge_id: String,
     },
-    SubmitTournamentScore {
-        tournament_id: String,
+    SubmitChallengeScore {
         username: String,
+        challenge_id: String,
         score: u64,
     },
-}
-
-// Add message types for cross-chain communication
-#[derive(Debug, Deserialize, Serialize)]
-pub enum FlappyMessage {
-    // Practice mode messages
-    UpdatePracticeBest {
+    ResolveChallenge {
         username: String,
-        score: u64,
-        player_chain_id: ChainId,
+        challenge_id: String,
+    },
+    ChallengeUpdate {
+        challenge: Challenge,
     },
     // Tournament mode messages
     SubmitTournamentScore {
@@ -137,6 +573,9 @@ pub enum FlappyMessage {
         username: String,
         score: u64,
         player_chain_id: ChainId,
+        ghost: Option<Vec<u8>>, // Encoded tap frames of the run
+        submitted_at: u64,      // Block time of the submission on the player chain
+        ticket: RunTicket,      // Ticket the run was started with
     },
     TournamentUpdate {
         tournament_id: String,
@@ -146,59 +585,732 @@ pub enum FlappyMessage {
         tournament_id: String,
         requester_chain_id: ChainId,
     },
+    // Leaderboard chain move, state sent from the old leaderboard chain to the new one
+    MoveLeaderboardUsers {
+        users: Vec<UserSnapshot>,
+    },
+    MoveLeaderboardTournaments {
+        tournaments: Vec<TournamentSnapshot>,
+    },
+    MoveLeaderboardChallenges {
+        challenges: Vec<Challenge>,
+    },
+    MoveLeaderboardHeadToHead {
+        records: Vec<((String, String), HeadToHead)>,
+    },
+    MoveLeaderboardPendingScores {
+        pending_scores: Vec<PendingScore>,
+    },
+    MoveLeaderboardLoginAttempts {
+        chain_attempts: Vec<(ChainId, LoginAttempts)>, // Failed logins per requesting chain
+    },
+    MoveLeaderboardBoards {
+        boards: BoardsSnapshot, // Sent last, the new chain takes over on it
+    },
+    LeaderboardMoveAccepted, // New leaderboard chain -> old one
+    LeaderboardMoveRejected, // Chain that isn't following the old one -> old one
+    // Message held or redirected by a former leaderboard chain, with the chain it came from
+    Forwarded {
+        origin_chain_id: ChainId,
+        message: Box<FlappyMessage>,
+    },
+}
+
+// State schema version; the contract upgrades older state one step at a time
+// 1: legacy high score board and practice history moved, 2: credentials salted,
+// 3: existing tournaments published for player chain replicas, 4: tournament entries counted,
+// 5: usernames indexed case-insensitively, 6: configured player chains subscribed to events,
+// 7: default reserved usernames seeded
+pub const SCHEMA_VERSION: u32 = 7;
+pub const MIGRATION_BATCH_SIZE: usize = 50; // Entries upgraded per operation or message
+
+// Keys to upgrade next, in order, after the last key a migration step handled
+pub fn migration_batch(mut keys: Vec<String>, cursor: Option<&str>, size: usize) -> Vec<String> {
+    keys.sort();
+    keys.into_iter()
+        .filter(|key| cursor.is_none_or(|cursor| key.as_str() > cursor))
+        .take(size)
+        .collect()
+}
+
+// Steps of a leaderboard chain move, in order
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, async_graphql::Enum, PartialEq, Eq)]
+pub enum LeaderboardMoveStep {
+    Users,
+    Tournaments,
+    Challenges,
+    HeadToHead,
+    PendingScores,
+    LoginAttempts,
+    Boards,
+    AwaitingNewChain, // Everything sent, waiting for the new chain to take over
+    Completed,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct LeaderboardMove {
+    pub new_chain_id: ChainId,
+    pub step: LeaderboardMoveStep,
+    #[graphql(skip)]
+    pub cursor: Option<String>, // Last key the current step sent
+    pub started_by: String, // Chain owner who started the move
+    pub started_at: u64, // timestamp
+}
+
+impl LeaderboardMove {
+    // Keys the running step sends next, moving on to `next_step` once they run out
+    pub fn next_batch(&mut self, keys: Vec<String>, next_step: LeaderboardMoveStep) -> Vec<String> {
+        let batch = migration_batch(keys, self.cursor.as_deref(), MIGRATION_BATCH_SIZE);
+        if batch.len() < MIGRATION_BATCH_SIZE {
+            self.step = next_step;
+            self.cursor = None;
+        } else {
+            self.cursor = batch.last().cloned();
+        }
+        batch
+    }
+}
+
+// A user's records on the leaderboard chain, sent to a new leaderboard chain
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserSnapshot {
+    pub user: User,
+    pub credential: Option<Credential>,
+    pub session: Option<LoginResult>, // Login session of the user's own chain
+    pub restriction: Option<AccountRestriction>,
+    pub practice_best: Option<PracticeEntry>,
+    pub practice_stats: Option<PracticeStats>,
+    pub practice_ghost: Option<GhostRun>,
+    pub achievements: Vec<Achievement>,
+    pub friends: Vec<String>,
+    pub chain_last_submission: Option<u64>, // Rate limit of the user's own chain
+    pub chain_last_ticket: Option<u64>, // Latest ticket ID used by the user's own chain
+    pub credential_reset: Option<CredentialReset>,
+    pub login_attempts: Option<LoginAttempts>,
+    pub tournaments_entered: u64,
+    pub hidden_tournament_results: Vec<(String, TournamentResult)>, // Results taken off live boards
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TournamentSnapshot {
+    pub tournament: Tournament,
+    pub leaderboard: Option<Vec<TournamentResult>>,
+    pub ghosts: Vec<(String, GhostRun)>, // Participant's best run
+}
+
+// Boards and settings of the whole leaderboard chain
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct BoardsSnapshot {
+    pub practice_leaderboard: Vec<PracticeEntry>,
+    pub global_practice_stats: PracticeStats,
+    pub pinned_tournaments: Vec<String>,
+    pub tournament_counter: u64,
+    pub challenge_counter: u64,
+    pub pending_score_counter: u64,
+    pub reserved_usernames: Vec<String>,
+    pub moderation_thresholds: ModerationThresholds,
+}
+
+// Entry of the legacy high score board, kept to read scores from old deployments
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct LeaderboardEntry {
+    pub player_name: String,
+    pub score: u64,
+    pub chain_id: ChainId,
+    pub timestamp: u64,
+}
+
+// Practice mode leaderboard entry
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct PracticeEntry {
+    pub username: String,
+    pub score: u64,
+    pub chain_id: ChainId,
+    pub timestamp: u64,
+}
+
+// A single practice run kept in a player chain's history
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PracticeRun {
+    pub score: u64,
+    pub timestamp: u64, // 0 for runs recorded before timestamps were kept
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct PracticeHistoryPage {
+    pub runs: Vec<PracticeRun>, // Most recent first
+    pub total_count: u64,
+    pub has_more: bool,
+}
+
+// Bounds for the number of runs kept in a player chain's practice history
+pub const DEFAULT_PRACTICE_HISTORY_CAP: u64 = 500;
+pub const MAX_PRACTICE_HISTORY_CAP: u64 = 10_000;
+
+// Lower bounds of the score buckets used by practice run histograms
+pub const PRACTICE_HISTOGRAM_BOUNDS: [u64; 7] = [0, 1, 5, 10, 25, 50, 100];
+
+// Number of practice runs a player chain batches before reporting them
+pub const PRACTICE_SUMMARY_BATCH_SIZE: u64 = 10;
+
+// Returns the histogram bucket index a score falls into
+pub fn histogram_bucket(score: u64) -> usize {
+    PRACTICE_HISTOGRAM_BOUNDS
+        .iter()
+        .rposition(|bound| score >= *bound)
+        .unwrap_or(0)
+}
+
+fn merge_buckets(into: &mut Vec<u64>, from: &[u64]) {
+    into.resize(PRACTICE_HISTOGRAM_BOUNDS.len(), 0);
+    for (total, count) in into.iter_mut().zip(from) {
+        *total += count;
+    }
+}
+
+// Batched practice runs sent from player chains to the leaderboard chain
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct PracticeRunsSummary {
+    pub count: u64,
+    pub sum: u64,
+    pub buckets: Vec<u64>, // Run count per PRACTICE_HISTOGRAM_BOUNDS bucket
+}
+
+impl PracticeRunsSummary {
+    pub fn record(&mut self, score: u64) {
+        self.count += 1;
+        self.sum += score;
+        self.buckets.resize(PRACTICE_HISTOGRAM_BOUNDS.len(), 0);
+        self.buckets[histogram_bucket(score)] += 1;
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    // Summaries from player chains must count runs in the PRACTICE_HISTOGRAM_BOUNDS buckets
+    pub fn has_valid_buckets(&self) -> bool {
+        self.buckets.len() == PRACTICE_HISTOGRAM_BOUNDS.len()
+            || (self.is_empty() && self.buckets.is_empty())
+    }
+}
+
+// Aggregated practice statistics kept on the leaderboard chain
+#[derive(Debug, Clone, Default, Deserialize, Serialize, async_graphql::SimpleObject)]
+#[graphql(complex)]
+pub struct PracticeStats {
+    pub total_runs: u64,
+    pub total_score: u64,
+    pub buckets: Vec<u64>, // Run count per PRACTICE_HISTOGRAM_BOUNDS bucket
+    pub players: u64, // Distinct players (only meaningful for global stats)
+    pub first_run_at: u64, // timestamp of the first reported batch
+    pub last_run_at: u64, // timestamp of the latest reported batch
+}
+
+impl PracticeStats {
+    pub fn add_runs(&mut self, summary: &PracticeRunsSummary, timestamp: u64) {
+        if summary.is_empty() || !summary.has_valid_buckets() {
+            return;
+        }
+        if self.total_runs == 0 {
+            self.first_run_at = timestamp;
+        }
+        self.total_runs += summary.count;
+        self.total_score += summary.sum;
+        merge_buckets(&mut self.buckets, &summary.buckets);
+        self.last_run_at = timestamp;
+    }
+}
+
+#[async_graphql::ComplexObject]
+impl PracticeStats {
+    async fn average_score(&self) -> f64 {
+        if self.total_runs == 0 {
+            0.0
+        } else {
+            self.total_score as f64 / self.total_runs as f64
+        }
+    }
+}
+
+// Tournament management structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
+pub enum TournamentStatus {
+    Registration, // Players can join
+    Active,      // Tournament is running
+    Ended,       // Tournament finished
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct Tournament {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub creator: String, // Admin who created it
+    pub status: TournamentStatus,
+    pub start_time: Option<u64>, // timestamp
+    pub end_time: Option<u64>, // timestamp
+    pub participants: Vec<String>, // usernames
+    pub results: Vec<TournamentResult>, // Final results
+    pub created_at: u64,
+    pub is_pinned: bool, // Admin can pin tournaments
+    pub pinned_at: Option<u64>, // When it was pinned
+    pub pinned_by: Option<String>, // Which admin pinned it
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject, async_graphql::InputObject)]
+#[graphql(input_name = "TournamentResultInput")]
+pub struct TournamentResult {
+    pub username: String,
+    pub score: u64,
+    pub rank: u32,
+    pub chain_id: ChainId,
+    pub timestamp: u64,
+}
+
+// Sorts results by score descending (highest first) and assigns ranks from 1
+pub fn rank_tournament_results(results: &mut [TournamentResult]) {
+    results.sort_by(|a, b| b.score.cmp(&a.score));
+    for (index, result) in results.iter_mut().enumerate() {
+        result.rank = (index + 1) as u32;
+    }
+}
+
+// Whether a run ending at `now` could have been played since the chain's previous submission
+pub fn submission_allowed(
+    game: &GameConstants,
+    score: u64,
+    last_submission_at: Option<u64>,
+    now: u64,
+) -> bool {
+    match last_submission_at {
+        Some(last_submission_at) => {
+            now.saturating_sub(last_submission_at) >= game.min_run_duration(score)
+        }
+        None => true,
+    }
+}
+
+// Ticket issued by StartRun on a player chain, each score must be submitted against an open one
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct RunTicket {
+    pub id: u64,
+    pub issued_at: u64, // timestamp
+}
+
+impl RunTicket {
+    // Whether a run started with this ticket can end at `now` with `score`
+    pub fn allows(&self, game: &GameConstants, score: u64, now: u64) -> bool {
+        now >= self.issued_at && now - self.issued_at >= game.min_run_duration(score)
+    }
+}
+
+// Score moderation structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
+pub enum FlagReason {
+    ScoreTooHigh, // Above the absolute maximum
+    JumpOverBest, // Beats the personal best by more than the allowed jump
+    TooFrequent,  // Came too soon after the previous score from the same chain
+}
+
+// Thresholds that send a score to the moderation queue, 0 disables a check (default: all off)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject, async_graphql::InputObject)]
+#[graphql(input_name = "ModerationThresholdsInput")]
+pub struct ModerationThresholds {
+    pub max_score: u64,
+    pub max_jump: u64, // Points above the personal best
+    pub min_submission_interval: u64, // Seconds between two scores from a player's chain
+}
+
+impl ModerationThresholds {
+    // `submission_gap` is the time since the chain's previous accepted score, None for its first
+    pub fn flag_reasons(
+        &self,
+        score: u64,
+        personal_best: Option<u64>,
+        submission_gap: Option<u64>,
+    ) -> Vec<FlagReason> {
+        let mut reasons = Vec::new();
+        if self.max_score > 0 && score > self.max_score {
+            reasons.push(FlagReason::ScoreTooHigh);
+        }
+        if self.max_jump > 0 && score > personal_best.unwrap_or(0).saturating_add(self.max_jump) {
+            reasons.push(FlagReason::JumpOverBest);
+        }
+        if submission_gap
+            .is_some_and(|gap| gap < self.min_submission_interval.saturating_mul(1_000_000))
+        {
+            reasons.push(FlagReason::TooFrequent);
+        }
+        reasons
+    }
+}
+
+// A flagged score held back from the leaderboards until an admin decides
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct PendingScore {
+    pub id: String,
+    pub username: String,
+    pub score: u64,
+    pub tournament_id: Option<String>, // None for practice scores
+    pub chain_id: ChainId,
+    pub submitted_at: u64, // timestamp
+    pub reasons: Vec<FlagReason>,
+    #[graphql(skip)]
+    pub ghost: Option<Vec<u8>>, // Encoded tap frames of the run
+}
+
+// Friends structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct Friend {
+    pub username: String,
+    pub since: u64, // timestamp
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct FriendRequest {
+    pub from_username: String,
+    pub to_username: String,
+    pub requested_at: u64, // timestamp
+}
+
+// Challenge structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
+pub enum ChallengeStatus {
+    Pending,   // Waiting for the opponent to accept
+    Accepted,  // Both players can submit a run
+    Completed, // Winner recorded
+    Expired,   // Deadline passed before the opponent accepted
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct Challenge {
+    pub id: String,
+    pub challenger: String,
+    pub opponent: String,
+    pub seed: u64,
+    pub deadline: u64, // timestamp
+    pub status: ChallengeStatus,
+    pub challenger_score: Option<u64>,
+    pub opponent_score: Option<u64>,
+    pub winner: Option<String>, // None on a draw
+    pub created_at: u64,
+    pub completed_at: Option<u64>,
+}
+
+impl Challenge {
+    pub fn is_participant(&self, username: &str) -> bool {
+        self.challenger == username || self.opponent == username
+    }
+
+    // Higher score wins; a missing run loses to any submitted run
+    pub fn decide_winner(&self) -> Option<String> {
+        match (self.challenger_score, self.opponent_score) {
+            (Some(a), Some(b)) if a > b => Some(self.challenger.clone()),
+            (Some(a), Some(b)) if b > a => Some(self.opponent.clone()),
+            (Some(_), None) => Some(self.challenger.clone()),
+            (None, Some(_)) => Some(self.opponent.clone()),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct HeadToHead {
+    pub player_a: String, // Alphabetically first player of the pair
+    pub player_b: String,
+    pub player_a_wins: u64,
+    pub player_b_wins: u64,
+    pub draws: u64,
+    pub last_played_at: u64, // timestamp
+}
+
+impl HeadToHead {
+    pub fn record(&mut self, winner: Option<&str>, timestamp: u64) {
+        match winner {
+            Some(winner) if winner == self.player_a => self.player_a_wins += 1,
+            Some(winner) if winner == self.player_b => self.player_b_wins += 1,
+            _ => self.draws += 1,
+        }
+        self.last_played_at = timestamp;
+    }
+}
+
+// Order-independent key for a pair of players
+pub fn head_to_head_key(first: &str, second: &str) -> (String, String) {
+    if first <= second {
+        (first.to_string(), second.to_string())
+    } else {
+        (second.to_string(), first.to_string())
+    }
+}
+
+// Ghost run structures
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq)]
+pub struct GhostRun {
+    pub score: u64,
+    pub data: Vec<u8>, // Delta + varint encoded tap frames
+    pub tap_count: u32,
+    pub recorded_at: u64, // timestamp
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct GhostReplay {
+    pub username: String,
+    pub context: String, // "practice" or a tournament ID
+    pub score: u64,
+    pub tap_frames: Vec<u32>,
+    pub recorded_at: u64,
+}
+
+// Ghost run context for practice bests; any other context is a tournament ID
+pub const PRACTICE_GHOST_CONTEXT: &str = "practice";
+
+// Limits on stored ghost runs
+pub const MAX_GHOST_TAPS: usize = 10_000;
+pub const MAX_GHOST_RUN_BYTES: usize = 16 * 1024;
+
+// Encodes strictly increasing tap frames as varint deltas
+pub fn encode_ghost_frames(frames: &[u32]) -> Result<Vec<u8>, String> {
+    if frames.len() > MAX_GHOST_TAPS {
+        return Err(format!("Ghost run exceeds {} taps", MAX_GHOST_TAPS));
+    }
+
+    let mut data = Vec::with_capacity(frames.len());
+    let mut previous = None;
+    for &frame in frames {
+        let mut delta = match previous {
+            Some(previous) if frame <= previous => {
+                return Err("Tap frames must be strictly increasing".to_string());
+            }
+            Some(previous) => frame - previous,
+            None => frame,
+        };
+        previous = Some(frame);
+
+        loop {
+            let byte = (delta & 0x7f) as u8;
+            delta >>= 7;
+            if delta == 0 {
+                data.push(byte);
+                break;
+            }
+            data.push(byte | 0x80);
+        }
+    }
+
+    if data.len() > MAX_GHOST_RUN_BYTES {
+        return Err(format!("Ghost run exceeds {} bytes", MAX_GHOST_RUN_BYTES));
+    }
+    Ok(data)
+}
+
+pub fn decode_ghost_frames(data: &[u8]) -> Result<Vec<u32>, String> {
+    let mut frames = Vec::new();
+    let mut frame: u32 = 0;
+    let mut delta: u32 = 0;
+    let mut shift = 0;
+
+    for &byte in data {
+        let bits = u32::from(byte & 0x7f);
+        if shift >= 32 || (bits << shift) >> shift != bits {
+            return Err("Malformed ghost run".to_string());
+        }
+        delta |= bits << shift;
+
+        if byte & 0x80 == 0 {
+            frame = frame
+                .checked_add(delta)
+                .ok_or_else(|| "Malformed ghost run".to_string())?;
+            frames.push(frame);
+            delta = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+
+    if shift != 0 {
+        return Err("Truncated ghost run".to_string());
+    }
+    Ok(frames)
+}
+
+// Achievement structures
+#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
+pub enum AchievementKind {
+    FirstRun,
+    Score10,
+    Score50,
+    Score100,
+    TenTournaments,
+    TournamentPodium,
+    WinStreak3,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, async_graphql::SimpleObject)]
+pub struct Achievement {
+    pub kind: AchievementKind,
+    pub unlocked_at: u64, // timestamp
+    pub tournament_id: Option<String>, // Tournament that unlocked it, if any
+}
+
+// Tournaments a player has to enter for the TenTournaments achievement
+pub const TOURNAMENTS_ENTERED_MILESTONE: u64 = 10;
+
+// Consecutive tournament wins needed for the WinStreak3 achievement
+pub const WIN_STREAK_MILESTONE: u64 = 3;
+
+// Score achievements reached by a single run
+pub fn score_achievements(score: u64) -> Vec<AchievementKind> {
+    [
+        (10, AchievementKind::Score10),
+        (50, AchievementKind::Score50),
+        (100, AchievementKind::Score100),
+    ]
+    .into_iter()
+    .filter(|(threshold, _)| score >= *threshold)
+    .map(|(_, kind)| kind)
+    .collect()
+}
+
+// Name of the event stream the leaderboard chain publishes on
+pub const FLAPPY_STREAM_NAME: &[u8] = b"flappy_events";
+
+// Events emitted by the leaderboard chain on FLAPPY_STREAM_NAME
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub enum FlappyEvent {
+    AchievementUnlocked {
+        username: String,
+        achievement: Achievement,
+    },
+    PracticeTopEntry {
+        entry: PracticeEntry,
+        rank: u32, // Position in the top 100, from 1
+    },
+    TournamentStatusChanged {
+        tournament_id: String,
+        status: TournamentStatus,
+    },
+    TournamentScoreUpdated {
+        tournament_id: String,
+        result: TournamentResult, // Player's new best with its live rank
+    },
+    UserRegistered {
+        user: UserProfile,
+    },
+    PracticeLeaderboardReset {
+        leaderboard: Vec<PracticeEntry>, // Whole top 100 after moderation changed it
+    },
+    TournamentUpdated {
+        tournament: Tournament,
+    },
+    TournamentDeleted {
+        tournament_id: String,
+    },
+    TournamentLeaderboardReset {
+        tournament_id: String,
+        leaderboard: Vec<TournamentResult>,
+    },
+    LeaderboardChainMoved {
+        leaderboard_chain_id: ChainId, // Chain that took over, player chains follow it
+    },
+}
+
+// Places a new top entry at its rank in a replica of the top 100, replacing the player's old entry
+pub fn insert_practice_top_entry(leaderboard: &mut Vec<PracticeEntry>, entry: PracticeEntry, rank: u32) {
+    leaderboard.retain(|existing| existing.username != entry.username);
+    let index = (rank.saturating_sub(1) as usize).min(leaderboard.len());
+    leaderboard.insert(index, entry);
+    leaderboard.truncate(100);
+}
+
+// Player profile structures
+#[derive(Debug, Clone, Default, Deserialize, Serialize, async_graphql::SimpleObject)]
+pub struct PlayerProfile {
+    pub username: String,
+    pub games_played: u64,
+    pub best_score: u64,
+    pub practice_rank: Option<u32>, // Position in the top 100
+    pub mean_score: f64,
+    pub median_score: u64,
+    pub p25_score: u64,
+    pub p75_score: u64,
+    pub p90_score: u64,
+    pub exact_percentiles: bool, // false when estimated from the practice histogram
+    pub tournaments_joined: u64,
+    pub tournament_placements: Vec<TournamentPlacement>,
+    pub tournament_wins: u64,
+    pub current_win_streak: u64, // Consecutive ended tournaments won, most recent first
+    pub longest_win_streak: u64,
 }
 
-// Leaderboard entry structure (for tournaments)
 #[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
-pub struct LeaderboardEntry {
-    pub player_name: String,
-    pub score: u64,
-    pub chain_id: ChainId,
-    pub timestamp: u64,
+pub struct TournamentPlacement {
+    pub tournament_id: String,
+    pub tournament_name: Option<String>, // None if the tournament isn't known on this chain
+    pub status: Option<TournamentStatus>,
+    pub best_score: Option<u64>,
+    pub rank: Option<u32>,
+    pub ended_at: Option<u64>,
 }
 
-// Practice mode leaderboard entry
-#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
-pub struct PracticeEntry {
+// Everything stored about a user, returned as JSON by exportUserData
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct UserDataExport {
     pub username: String,
-    pub score: u64,
-    pub chain_id: ChainId,
-    pub timestamp: u64,
+    pub exported_at: u64, // timestamp
+    pub user: Option<UserProfile>,
+    pub restriction: Option<AccountRestriction>,
+    pub practice_best: Option<PracticeEntry>,
+    pub practice_stats: Option<PracticeStats>,
+    pub practice_history: Vec<PracticeRun>, // Oldest first, only kept on the player's own chain
+    pub achievements: Vec<Achievement>,
+    pub friends: Vec<String>,
+    pub tournaments: Vec<TournamentPlacement>,
+    pub tournament_scores: std::collections::BTreeMap<String, Vec<u64>>, // tournament_id -> own runs
+    pub challenges: Vec<Challenge>,
 }
 
-// Tournament management structures
-#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::Enum, Copy, PartialEq, Eq)]
-pub enum TournamentStatus {
-    Registration, // Players can join
-    Active,      // Tournament is running
-    Ended,       // Tournament finished
+// Nearest-rank percentile of an ascending list of scores
+pub fn score_percentile(sorted_scores: &[u64], percentile: u64) -> u64 {
+    if sorted_scores.is_empty() {
+        return 0;
+    }
+    let rank = (percentile.min(100) as usize * sorted_scores.len()).div_ceil(100);
+    sorted_scores[rank.max(1) - 1]
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject)]
-pub struct Tournament {
-    pub id: String,
-    pub name: String,
-    pub description: String,
-    pub creator: String, // Admin who created it
-    pub status: TournamentStatus,
-    pub start_time: Option<u64>, // timestamp
-    pub end_time: Option<u64>, // timestamp
-    pub participants: Vec<String>, // usernames
-    pub results: Vec<TournamentResult>, // Final results
-    pub created_at: u64,
-    pub is_pinned: bool, // Admin can pin tournaments
-    pub pinned_at: Option<u64>, // When it was pinned
-    pub pinned_by: Option<String>, // Which admin pinned it
+// Nearest-rank percentile estimated from a practice histogram (bucket lower bound)
+pub fn histogram_percentile(buckets: &[u64], percentile: u64) -> u64 {
+    let total: u64 = buckets.iter().sum();
+    if total == 0 {
+        return 0;
+    }
+    let rank = (percentile.min(100) * total).div_ceil(100).max(1);
+    let mut seen = 0;
+    for (index, count) in buckets.iter().enumerate() {
+        seen += count;
+        if seen >= rank {
+            return PRACTICE_HISTOGRAM_BOUNDS.get(index).copied().unwrap_or(0);
+        }
+    }
+    0
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize, async_graphql::SimpleObject, async_graphql::InputObject)]
-#[graphql(input_name = "TournamentResultInput")]
-pub struct TournamentResult {
-    pub username: String,
-    pub score: u64,
-    pub rank: u32,
-    pub chain_id: ChainId,
-    pub timestamp: u64,
+// Returns (current, longest) streaks of wins from results ordered oldest first
+pub fn win_streaks(wins: &[bool]) -> (u64, u64) {
+    let mut current = 0;
+    let mut longest = 0;
+    for won in wins {
+        if *won {
+            current += 1;
+            longest = longest.max(current);
+        } else {
+            current = 0;
+        }
+    }
+    (current, longest)
 }
 
 #[cfg(test)]
@@ -265,19 +1377,31 @@ mod tests {
     fn test_application_parameters_default() {
         let params = ApplicationParameters::default();
         assert!(params.leaderboard_chain_id.is_none());
+        assert_eq!(params.game, GameConstants::default());
+
+        // Parameters from before game constants existed still load
+        let params: ApplicationParameters =
+            serde_json::from_str(r#"{"leaderboard_chain_id": null}"#).unwrap();
+        assert_eq!(params.game, GameConstants::default());
+        let params: ApplicationParameters =
+            serde_json::from_str(r#"{"leaderboard_chain_id": null, "game": {"pipe_gap": 120}}"#).unwrap();
+        assert_eq!(params.game.pipe_gap, 120);
+        assert_eq!(params.game.gravity, 300);
     }
 
     #[test]
     fn test_operations_serialization() {
         let op = Operation::SubmitPracticeScore { 
             username: "test_user".to_string(),
-            score: 42 
+            score: 42,
+            tap_frames: None,
+            ticket_id: 1,
         };
         let serialized = serde_json::to_string(&op).unwrap();
         let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            Operation::SubmitPracticeScore { username, score } => {
+            Operation::SubmitPracticeScore { username, score, .. } => {
                 assert_eq!(username, "test_user");
                 assert_eq!(score, 42);
             },
@@ -292,6 +1416,9 @@ mod tests {
             username: "test".to_string(),
             score: 500,
             player_chain_id: chain_id,
+            ghost: None,
+            submitted_at: 0,
+            ticket: RunTicket { id: 1, issued_at: 0 },
         };
 
         match message {
@@ -320,6 +1447,28 @@ mod tests {
         assert_eq!(user.created_at, 1234567890);
     }
 
+    #[test]
+    fn test_user_profile_from_user() {
+        let chain_id = test_chain_id();
+        let user = User {
+            username: "testuser".to_string(),
+            hash: "hash123".to_string(),
+            role: UserRole::Admin,
+            created_at: 1234567890,
+            chain_id: Some(chain_id),
+        };
+
+        let profile = UserProfile::from(&user);
+        assert_eq!(profile.username, "testuser");
+        assert_eq!(profile.role, UserRole::Admin);
+        assert_eq!(profile.created_at, 1234567890);
+        assert_eq!(profile.chain_id, Some(chain_id));
+
+        // No credential material survives serialization of the public type
+        let serialized = serde_json::to_string(&profile).unwrap();
+        assert!(!serialized.contains("hash"));
+    }
+
     #[test]
     fn test_user_role() {
         let admin = UserRole::Admin;
@@ -395,21 +1544,18 @@ mod tests {
 
     #[test]
     fn test_login_operation_serialization() {
-        let chain_id = test_chain_id();
         let op = Operation::LoginOrRegister {
             username: "testuser".to_string(),
             hash: "testhash".to_string(),
-            requester_chain_id: chain_id,
         };
         
         let serialized = serde_json::to_string(&op).unwrap();
         let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            Operation::LoginOrRegister { username, hash, requester_chain_id } => {
+            Operation::LoginOrRegister { username, hash } => {
                 assert_eq!(username, "testuser");
                 assert_eq!(hash, "testhash");
-                assert_eq!(requester_chain_id, chain_id);
             },
             _ => panic!("Wrong operation type"),
         }
@@ -436,15 +1582,18 @@ mod tests {
         let op = Operation::SubmitPracticeScore {
             username: "player1".to_string(),
             score: 750,
+            tap_frames: Some(vec![10, 45, 80]),
+            ticket_id: 1,
         };
         
         let serialized = serde_json::to_string(&op).unwrap();
         let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            Operation::SubmitPracticeScore { username, score } => {
+            Operation::SubmitPracticeScore { username, score, tap_frames, .. } => {
                 assert_eq!(username, "player1");
                 assert_eq!(score, 750);
+                assert_eq!(tap_frames, Some(vec![10, 45, 80]));
             },
             _ => panic!("Wrong operation type"),
         }
@@ -457,13 +1606,16 @@ mod tests {
             username: "player1".to_string(),
             score: 1000,
             player_chain_id: chain_id,
+            ghost: None,
+            submitted_at: 0,
+            ticket: RunTicket { id: 1, issued_at: 0 },
         };
         
         let serialized = serde_json::to_string(&message).unwrap();
         let deserialized: FlappyMessage = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            FlappyMessage::UpdatePracticeBest { username, score, player_chain_id } => {
+            FlappyMessage::UpdatePracticeBest { username, score, player_chain_id, .. } => {
                 assert_eq!(username, "player1");
                 assert_eq!(score, 1000);
                 assert_eq!(player_chain_id, chain_id);
@@ -472,6 +1624,444 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_histogram_bucket() {
+        assert_eq!(histogram_bucket(0), 0);
+        assert_eq!(histogram_bucket(1), 1);
+        assert_eq!(histogram_bucket(9), 2);
+        assert_eq!(histogram_bucket(10), 3);
+        assert_eq!(histogram_bucket(99), 5);
+        assert_eq!(histogram_bucket(5000), 6);
+    }
+
+    #[test]
+    fn test_practice_runs_summary_record() {
+        let mut summary = PracticeRunsSummary::default();
+        assert!(summary.is_empty());
+
+        summary.record(0);
+        summary.record(12);
+        summary.record(14);
+
+        assert_eq!(summary.count, 3);
+        assert_eq!(summary.sum, 26);
+        assert_eq!(summary.buckets, vec![1, 0, 0, 2, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_practice_stats_add_runs() {
+        let mut first = PracticeRunsSummary::default();
+        first.record(3);
+        let mut second = PracticeRunsSummary::default();
+        second.record(60);
+        second.record(100);
+
+        let mut stats = PracticeStats::default();
+        stats.add_runs(&first, 1000);
+        stats.add_runs(&second, 2000);
+        stats.add_runs(&PracticeRunsSummary::default(), 3000);
+
+        assert_eq!(stats.total_runs, 3);
+        assert_eq!(stats.total_score, 163);
+        assert_eq!(stats.buckets, vec![0, 1, 0, 0, 0, 1, 1]);
+        assert_eq!(stats.first_run_at, 1000);
+        assert_eq!(stats.last_run_at, 2000);
+    }
+
+    #[test]
+    fn test_practice_runs_summary_buckets() {
+        assert!(PracticeRunsSummary::default().has_valid_buckets());
+
+        let mut summary = PracticeRunsSummary::default();
+        summary.record(3);
+        assert!(summary.has_valid_buckets());
+
+        // Summaries with a different bucket count are left out of the statistics
+        summary.buckets.push(1);
+        assert!(!summary.has_valid_buckets());
+        let mut stats = PracticeStats::default();
+        stats.add_runs(&summary, 1000);
+        assert_eq!(stats.total_runs, 0);
+        assert!(stats.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_practice_runs_summary_message_serialization() {
+        let chain_id = test_chain_id();
+        let mut summary = PracticeRunsSummary::default();
+        summary.record(7);
+        let message = FlappyMessage::PracticeRunsSummary {
+            username: "player1".to_string(),
+            summary: summary.clone(),
+            player_chain_id: chain_id,
+        };
+
+        let serialized = serde_json::to_string(&message).unwrap();
+        let deserialized: FlappyMessage = serde_json::from_str(&serialized).unwrap();
+
+        match deserialized {
+            FlappyMessage::PracticeRunsSummary { username, summary: received, player_chain_id } => {
+                assert_eq!(username, "player1");
+                assert_eq!(received, summary);
+                assert_eq!(player_chain_id, chain_id);
+            },
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_score_percentile() {
+        let scores = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 10];
+        assert_eq!(score_percentile(&scores, 50), 5);
+        assert_eq!(score_percentile(&scores, 90), 9);
+        assert_eq!(score_percentile(&scores, 100), 10);
+        assert_eq!(score_percentile(&scores, 0), 1);
+        assert_eq!(score_percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_histogram_percentile() {
+        // 2 runs at 0, 1 run in 5..10, 1 run at 100+
+        let buckets = vec![2, 0, 1, 0, 0, 0, 1];
+        assert_eq!(histogram_percentile(&buckets, 25), 0);
+        assert_eq!(histogram_percentile(&buckets, 75), 5);
+        assert_eq!(histogram_percentile(&buckets, 90), 100);
+        assert_eq!(histogram_percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_win_streaks() {
+        assert_eq!(win_streaks(&[]), (0, 0));
+        assert_eq!(win_streaks(&[true, true, false, true]), (1, 2));
+        assert_eq!(win_streaks(&[false, true, true, true]), (3, 3));
+    }
+
+    #[test]
+    fn test_score_achievements() {
+        assert!(score_achievements(9).is_empty());
+        assert_eq!(score_achievements(10), vec![AchievementKind::Score10]);
+        assert_eq!(
+            score_achievements(120),
+            vec![AchievementKind::Score10, AchievementKind::Score50, AchievementKind::Score100]
+        );
+    }
+
+    #[test]
+    fn test_achievement_event_serialization() {
+        let event = FlappyEvent::AchievementUnlocked {
+            username: "player1".to_string(),
+            achievement: Achievement {
+                kind: AchievementKind::TournamentPodium,
+                unlocked_at: 1234567890,
+                tournament_id: Some("tournament_0".to_string()),
+            },
+        };
+
+        let serialized = serde_json::to_string(&event).unwrap();
+        let deserialized: FlappyEvent = serde_json::from_str(&serialized).unwrap();
+
+        match deserialized {
+            FlappyEvent::AchievementUnlocked { username, achievement } => {
+                assert_eq!(username, "player1");
+                assert_eq!(achievement.kind, AchievementKind::TournamentPodium);
+                assert_eq!(achievement.tournament_id.as_deref(), Some("tournament_0"));
+            },
+            _ => panic!("Wrong event type"),
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_event_serialization() {
+        let chain_id = test_chain_id();
+        let event = FlappyEvent::TournamentScoreUpdated {
+            tournament_id: "tournament_0".to_string(),
+            result: TournamentResult {
+                username: "player1".to_string(),
+                score: 42,
+                rank: 2,
+                chain_id,
+                timestamp: 1234567890,
+            },
+        };
+
+        let serialized = serde_json::to_string(&event).unwrap();
+        let deserialized: FlappyEvent = serde_json::from_str(&serialized).unwrap();
+
+        match deserialized {
+            FlappyEvent::TournamentScoreUpdated { tournament_id, result } => {
+                assert_eq!(tournament_id, "tournament_0");
+                assert_eq!(result.score, 42);
+                assert_eq!(result.rank, 2);
+            },
+            _ => panic!("Wrong event type"),
+        }
+    }
+
+    #[test]
+    fn test_migration_batch() {
+        let keys = vec!["carol".to_string(), "alice".to_string(), "bob".to_string()];
+
+        assert_eq!(migration_batch(keys.clone(), None, 2), vec!["alice", "bob"]);
+        assert_eq!(migration_batch(keys.clone(), Some("bob"), 2), vec!["carol"]);
+        assert!(migration_batch(keys, Some("carol"), 2).is_empty());
+    }
+
+    #[test]
+    fn test_leaderboard_move_batches() {
+        let mut leaderboard_move = LeaderboardMove {
+            new_chain_id: test_chain_id(),
+            step: LeaderboardMoveStep::Users,
+            cursor: None,
+            started_by: "owner".to_string(),
+            started_at: 0,
+        };
+        let keys: Vec<String> = (0..MIGRATION_BATCH_SIZE + 1)
+            .map(|n| format!("user_{:03}", n))
+            .collect();
+
+        // A full batch keeps the step going, a short one moves on
+        let batch = leaderboard_move.next_batch(keys.clone(), LeaderboardMoveStep::Tournaments);
+        assert_eq!(batch.len(), MIGRATION_BATCH_SIZE);
+        assert_eq!(leaderboard_move.step, LeaderboardMoveStep::Users);
+        assert_eq!(leaderboard_move.cursor, batch.last().cloned());
+
+        let batch = leaderboard_move.next_batch(keys, LeaderboardMoveStep::Tournaments);
+        assert_eq!(batch, vec![format!("user_{:03}", MIGRATION_BATCH_SIZE)]);
+        assert_eq!(leaderboard_move.step, LeaderboardMoveStep::Tournaments);
+        assert_eq!(leaderboard_move.cursor, None);
+    }
+
+    #[test]
+    fn test_insert_practice_top_entry() {
+        let entry = |username: &str, score| PracticeEntry {
+            username: username.to_string(),
+            score,
+            chain_id: test_chain_id(),
+            timestamp: 0,
+        };
+        let mut leaderboard = vec![entry("alice", 30), entry("bob", 20), entry("carol", 10)];
+
+        // Carol's new best moves her to the top without leaving her old entry behind
+        insert_practice_top_entry(&mut leaderboard, entry("carol", 40), 1);
+        let names: Vec<&str> = leaderboard.iter().map(|e| e.username.as_str()).collect();
+        assert_eq!(names, vec!["carol", "alice", "bob"]);
+
+        // Ranks past the end append
+        insert_practice_top_entry(&mut leaderboard, entry("dave", 5), 10);
+        assert_eq!(leaderboard.last().unwrap().username, "dave");
+
+        // The replica never grows past the top 100
+        for i in 0..120 {
+            insert_practice_top_entry(&mut leaderboard, entry(&format!("p{}", i), 1), 200);
+        }
+        assert_eq!(leaderboard.len(), 100);
+        assert_eq!(leaderboard[0].username, "carol");
+    }
+
+    #[test]
+    fn test_friend_operations_serialization() {
+        let op = Operation::AddFriend {
+            username: "alice".to_string(),
+            friend_username: "bob".to_string(),
+        };
+
+        let serialized = serde_json::to_string(&op).unwrap();
+        let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
+
+        match deserialized {
+            Operation::AddFriend { username, friend_username } => {
+                assert_eq!(username, "alice");
+                assert_eq!(friend_username, "bob");
+            },
+            _ => panic!("Wrong operation type"),
+        }
+    }
+
+    #[test]
+    fn test_friend_message_serialization() {
+        let message = FlappyMessage::FriendAccepted {
+            username: "bob".to_string(),
+            friend_username: "alice".to_string(),
+        };
+
+        let serialized = serde_json::to_string(&message).unwrap();
+        let deserialized: FlappyMessage = serde_json::from_str(&serialized).unwrap();
+
+        match deserialized {
+            FlappyMessage::FriendAccepted { username, friend_username } => {
+                assert_eq!(username, "bob");
+                assert_eq!(friend_username, "alice");
+            },
+            _ => panic!("Wrong message type"),
+        }
+    }
+
+    #[test]
+    fn test_head_to_head_key() {
+        assert_eq!(
+            head_to_head_key("bob", "alice"),
+            ("alice".to_string(), "bob".to_string())
+        );
+        assert_eq!(head_to_head_key("alice", "bob"), head_to_head_key("bob", "alice"));
+    }
+
+    #[test]
+    fn test_challenge_winner() {
+        let mut challenge = Challenge {
+            id: "challenge_0".to_string(),
+            challenger: "alice".to_string(),
+            opponent: "bob".to_string(),
+            seed: 42,
+            deadline: 1_000,
+            status: ChallengeStatus::Accepted,
+            challenger_score: None,
+            opponent_score: None,
+            winner: None,
+            created_at: 0,
+            completed_at: None,
+        };
+        assert_eq!(challenge.decide_winner(), None);
+
+        challenge.opponent_score = Some(3);
+        assert_eq!(challenge.decide_winner(), Some("bob".to_string()));
+
+        challenge.challenger_score = Some(7);
+        assert_eq!(challenge.decide_winner(), Some("alice".to_string()));
+
+        challenge.opponent_score = Some(7);
+        assert_eq!(challenge.decide_winner(), None);
+    }
+
+    #[test]
+    fn test_head_to_head_record() {
+        let (player_a, player_b) = head_to_head_key("bob", "alice");
+        let mut record = HeadToHead {
+            player_a,
+            player_b,
+            ..HeadToHead::default()
+        };
+
+        record.record(Some("alice"), 10);
+        record.record(Some("bob"), 20);
+        record.record(Some("alice"), 30);
+        record.record(None, 40);
+
+        assert_eq!(record.player_a_wins, 2);
+        assert_eq!(record.player_b_wins, 1);
+        assert_eq!(record.draws, 1);
+        assert_eq!(record.last_played_at, 40);
+    }
+
+    #[test]
+    fn test_ghost_frames_round_trip() {
+        let frames = vec![0, 12, 40, 41, 300, 70_000];
+        let data = encode_ghost_frames(&frames).unwrap();
+
+        // Small gaps between taps take a single byte each
+        assert_eq!(data.len(), 1 + 1 + 1 + 1 + 2 + 3);
+        assert_eq!(decode_ghost_frames(&data).unwrap(), frames);
+        assert_eq!(decode_ghost_frames(&[]).unwrap(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn test_ghost_frames_validation() {
+        assert!(encode_ghost_frames(&[5, 5]).is_err());
+        assert!(encode_ghost_frames(&[10, 3]).is_err());
+
+        let too_many: Vec<u32> = (0..=MAX_GHOST_TAPS as u32).collect();
+        assert!(encode_ghost_frames(&too_many).is_err());
+
+        // Continuation bit set on the last byte
+        assert!(decode_ghost_frames(&[0x85]).is_err());
+        // Delta wider than 32 bits
+        assert!(decode_ghost_frames(&[0xff, 0xff, 0xff, 0xff, 0x7f]).is_err());
+    }
+
+    #[test]
+    fn test_credential_verification() {
+        let credential = Credential::new(derive_credential_salt("seed"), "client_hash");
+
+        assert_eq!(credential.version, CREDENTIAL_HASH_VERSION);
+        assert!(credential.verify("client_hash"));
+        assert!(!credential.verify("other_hash"));
+        assert_ne!(credential.hash, "client_hash");
+
+        // Same credential under a different salt hashes differently
+        let other = Credential::new(derive_credential_salt("other seed"), "client_hash");
+        assert_ne!(credential.salt, other.salt);
+        assert_ne!(credential.hash, other.hash);
+
+        // An unknown version fails the login instead of panicking
+        let unknown = Credential {
+            version: CREDENTIAL_HASH_VERSION + 1,
+            ..credential
+        };
+        assert!(!unknown.verify("client_hash"));
+    }
+
+    #[test]
+    fn test_hash_reset_token() {
+        assert_eq!(
+            hash_reset_token("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_ne!(hash_reset_token("abc"), hash_reset_token("abd"));
+    }
+
+    #[test]
+    fn test_login_attempts_backoff() {
+        let mut attempts = LoginAttempts::default();
+        let now = 1_000_000_000;
+
+        // Free attempts don't lock
+        for _ in 1..LOGIN_FREE_ATTEMPTS {
+            attempts.record_failure(now);
+            assert!(!attempts.is_locked(now));
+        }
+
+        attempts.record_failure(now);
+        assert!(attempts.is_locked(now));
+        assert_eq!(attempts.locked_until, now + LOGIN_BASE_LOCKOUT);
+        assert!(!attempts.is_locked(now + LOGIN_BASE_LOCKOUT));
+
+        // Each further failure doubles the lockout, up to the maximum
+        attempts.record_failure(now);
+        assert_eq!(attempts.locked_until, now + 2 * LOGIN_BASE_LOCKOUT);
+        for _ in 0..40 {
+            attempts.record_failure(now);
+        }
+        assert_eq!(attempts.locked_until, now + LOGIN_MAX_LOCKOUT);
+
+        // Old failures are forgotten
+        let later = now + LOGIN_FAILURE_WINDOW + 1;
+        attempts.record_failure(later);
+        assert_eq!(attempts.failures, 1);
+    }
+
+    #[test]
+    fn test_validate_username() {
+        assert!(validate_username("alice").is_ok());
+        assert!(validate_username("flappy_bird-42").is_ok());
+        assert!(validate_username("abc").is_ok());
+        assert!(validate_username(&"a".repeat(MAX_USERNAME_LENGTH)).is_ok());
+
+        assert!(validate_username("").is_err());
+        assert!(validate_username("ab").is_err());
+        assert!(validate_username(&"a".repeat(MAX_USERNAME_LENGTH + 1)).is_err());
+        assert!(validate_username("   ").is_err());
+        assert!(validate_username("bad name").is_err());
+        assert!(validate_username("аdmin").is_err()); // Cyrillic 'а'
+    }
+
+    #[test]
+    fn test_normalize_username() {
+        assert_eq!(normalize_username("Admin"), normalize_username("admin"));
+        assert_eq!(normalize_username("adm1n"), normalize_username("admin"));
+        assert_eq!(normalize_username("AD_MIN"), normalize_username("admin"));
+        assert_eq!(normalize_username("r00t"), normalize_username("root"));
+        assert_ne!(normalize_username("alice"), normalize_username("admin"));
+    }
+
     #[test]
     fn test_tournament_creation() {
         let _chain_id = test_chain_id();
@@ -600,13 +2190,16 @@ mod tests {
             username: "player1".to_string(),
             score: 1000,
             player_chain_id: chain_id,
+            ghost: None,
+            submitted_at: 0,
+            ticket: RunTicket { id: 1, issued_at: 0 },
         };
         
         let serialized = serde_json::to_string(&message).unwrap();
         let deserialized: FlappyMessage = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            FlappyMessage::SubmitTournamentScore { tournament_id, username, score, player_chain_id } => {
+            FlappyMessage::SubmitTournamentScore { tournament_id, username, score, player_chain_id, .. } => {
                 assert_eq!(tournament_id, "tournament1");
                 assert_eq!(username, "player1");
                 assert_eq!(score, 1000);
@@ -622,13 +2215,15 @@ mod tests {
             tournament_id: "tournament_123".to_string(),
             username: "player1".to_string(),
             score: 1500,
+            tap_frames: None,
+            ticket_id: 1,
         };
         
         let serialized = serde_json::to_string(&op).unwrap();
         let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            Operation::SubmitTournamentScore { tournament_id, username, score } => {
+            Operation::SubmitTournamentScore { tournament_id, username, score, .. } => {
                 assert_eq!(tournament_id, "tournament_123");
                 assert_eq!(username, "player1");
                 assert_eq!(score, 1500);
@@ -663,6 +2258,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::inconsistent_digit_grouping)]
     fn test_tournament_auto_start_timing() {
         let current_time = 1234567890_000_000u64; // Current time in microseconds
         let start_time = 1234567800_000_000u64;   // Start time 90 seconds ago
@@ -674,6 +2270,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::inconsistent_digit_grouping)]
     fn test_tournament_auto_end_timing() {
         let current_time = 1234568100_000_000u64; // Current time in microseconds
         let start_time = 1234567800_000_000u64;   // Start time 300 seconds ago
@@ -712,6 +2309,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::inconsistent_digit_grouping)]
     fn test_tournament_without_scheduled_times() {
         let tournament = Tournament {
             id: "manual_tournament".to_string(),
@@ -734,21 +2332,143 @@ mod tests {
         assert!(tournament.end_time.is_none());
     }
 
+    #[test]
+    fn test_rank_tournament_results() {
+        let chain_id = test_chain_id();
+        let mut results: Vec<TournamentResult> = [("alice", 10), ("bob", 30), ("carol", 20)]
+            .into_iter()
+            .map(|(username, score)| TournamentResult {
+                username: username.to_string(),
+                score,
+                rank: 0,
+                chain_id,
+                timestamp: 0,
+            })
+            .collect();
+
+        rank_tournament_results(&mut results);
+        let ranked: Vec<(&str, u32)> = results
+            .iter()
+            .map(|result| (result.username.as_str(), result.rank))
+            .collect();
+        assert_eq!(ranked, vec![("bob", 1), ("carol", 2), ("alice", 3)]);
+    }
+
+    #[test]
+    fn test_submission_interval() {
+        // 28 frames to fall 120px at 0.3px per frame squared, then 120 frames per pipe
+        let game = GameConstants::default();
+        let fall = game.min_run_duration(0);
+        assert_eq!(fall, 28 * 1_000_000 / FRAMES_PER_SECOND);
+        assert_eq!(game.min_run_duration(10), (28 + 10 * 120) * 1_000_000 / FRAMES_PER_SECOND);
+        assert_eq!(game.min_run_duration(u64::MAX), u64::MAX / FRAMES_PER_SECOND);
+
+        // Faster pipes make for shorter runs
+        let fast = GameConstants {
+            pipe_speed: 3_000,
+            ..game
+        };
+        assert!(fast.min_run_duration(10) < game.min_run_duration(10));
+
+        // Constants under which runs take no time are rejected
+        assert!(game.is_playable());
+        assert!(!GameConstants { pipe_spacing: 0, ..game }.is_playable());
+        assert!(!GameConstants { fall_height: 0, ..game }.is_playable());
+        assert!(!GameConstants { gravity: 0, ..game }.is_playable());
+        assert!(!GameConstants { pipe_speed: 0, ..game }.is_playable());
+        assert!(!GameConstants { pipe_speed: 200_000, ..game }.is_playable());
+
+        let last = 50_000_000;
+        assert!(submission_allowed(&game, 100, None, 0));
+        assert!(submission_allowed(&game, 0, Some(last), last + fall));
+        assert!(!submission_allowed(&game, 0, Some(last), last + fall - 1));
+        assert!(!submission_allowed(&game, 5, Some(last), last + game.min_run_duration(4)));
+        assert!(submission_allowed(&game, 5, Some(last), last + game.min_run_duration(5)));
+    }
+
+    #[test]
+    fn test_run_ticket() {
+        let ticket = RunTicket {
+            id: 7,
+            issued_at: 10_000_000,
+        };
+
+        let game = GameConstants::default();
+        assert!(ticket.allows(&game, 3, 10_000_000 + game.min_run_duration(3)));
+        assert!(!ticket.allows(&game, 3, 10_000_000 + game.min_run_duration(3) - 1));
+        assert!(!ticket.allows(&game, 0, 9_000_000));
+    }
+
+    #[test]
+    fn test_moderation_flag_reasons() {
+        let thresholds = ModerationThresholds {
+            max_score: 500,
+            max_jump: 100,
+            min_submission_interval: 30,
+        };
+
+        assert!(thresholds.flag_reasons(150, Some(60), None).is_empty());
+        assert_eq!(
+            thresholds.flag_reasons(170, Some(60), Some(60_000_000)),
+            vec![FlagReason::JumpOverBest]
+        );
+        assert_eq!(
+            thresholds.flag_reasons(600, None, Some(29_999_999)),
+            vec![
+                FlagReason::ScoreTooHigh,
+                FlagReason::JumpOverBest,
+                FlagReason::TooFrequent
+            ]
+        );
+        assert!(thresholds.flag_reasons(50, None, Some(30_000_000)).is_empty());
+
+        // The default thresholds disable every check
+        let disabled = ModerationThresholds::default();
+        assert!(disabled.flag_reasons(u64::MAX, None, Some(0)).is_empty());
+    }
+
+    #[test]
+    fn test_account_restriction_expiry() {
+        let suspension = AccountRestriction {
+            kind: RestrictionKind::Suspended,
+            reason: "spam".to_string(),
+            issued_by: "admin".to_string(),
+            issued_at: 1000,
+            expires_at: Some(2000),
+            scores_hidden: true,
+        };
+        assert!(suspension.is_active(1999));
+        assert!(suspension.hides_scores(1999));
+        assert!(!suspension.is_active(2000));
+        assert!(!suspension.hides_scores(2000));
+
+        let ban = AccountRestriction {
+            kind: RestrictionKind::Banned,
+            expires_at: None,
+            scores_hidden: false,
+            ..suspension
+        };
+        assert!(ban.is_active(u64::MAX));
+        assert!(!ban.hides_scores(u64::MAX));
+    }
+
     #[test]
     fn test_delete_user_operation_serialization() {
         let chain_id = test_chain_id();
         let op = Operation::DeleteUser {
             caller_chain_id: chain_id,
             username: "testuser".to_string(),
+            purge_scores: true,
         };
         
         let serialized = serde_json::to_string(&op).unwrap();
         let deserialized: Operation = serde_json::from_str(&serialized).unwrap();
         
         match deserialized {
-            Operation::DeleteUser { caller_chain_id, username } => {
+            Operation::DeleteUser { caller_chain_id, username, purge_scores } => {
                 assert_eq!(caller_chain_id, chain_id);
                 assert_eq!(username, "testuser");
+                assert!(purge_scores);
             },
             _ => panic!("Wrong operation type"),
         }
@@ -4,18 +4,28 @@ mod state;
 
 use std::sync::Arc;
 
-use async_graphql::{EmptySubscription, Object, Schema};
+use async_graphql::{EmptySubscription, Json, Object, Schema};
 use linera_sdk::{
-    graphql::GraphQLMutationRoot, linera_base_types::WithServiceAbi, views::View, Service,
-    ServiceRuntime,
+    graphql::GraphQLMutationRoot,
+    linera_base_types::{ChainId, WithServiceAbi},
+    views::View,
+    Service, ServiceRuntime,
 };
 
-use flappy::{ApplicationParameters, LeaderboardEntry, Operation, User, LoginResult, PracticeEntry, Tournament, TournamentResult};
+use flappy::{
+    decode_ghost_frames, head_to_head_key, histogram_percentile, rank_tournament_results,
+    score_percentile, win_streaks, AccountRestriction, Achievement, ApplicationParameters,
+    Challenge, Friend, FriendRequest, GameConstants, GhostReplay, GhostRun, HeadToHead,
+    LeaderboardMove, LoginAttempts, LoginResult, ModerationThresholds, Operation, PendingScore,
+    PlayerProfile, PracticeEntry, PracticeHistoryPage, PracticeRun, PracticeRunsSummary,
+    PracticeStats, RunTicket, Tournament, TournamentPlacement, TournamentResult, TournamentStatus,
+    UserDataExport, UserProfile, PRACTICE_GHOST_CONTEXT, PRACTICE_HISTOGRAM_BOUNDS,
+};
 
 use self::state::FlappyState;
 
 pub struct FlappyService {
-    state: FlappyState,
+    state: Arc<FlappyState>,
     runtime: Arc<ServiceRuntime<Self>>,
 }
 
@@ -33,7 +43,7 @@ impl Service for FlappyService {
             .await
             .expect("Failed to load state");
         FlappyService {
-            state,
+            state: Arc::new(state),
             runtime: Arc::new(runtime),
         }
     }
@@ -41,10 +51,23 @@ impl Service for FlappyService {
     async fn handle_query(&self, query: Self::Query) -> Self::QueryResponse {
         let player_name = self.state.player_name.get().clone();
         let is_leaderboard = *self.state.is_leaderboard_chain.get();
-        let leaderboard = self.state.top_leaderboard.get().clone();
+        let schema_version = *self.state.schema_version.get();
+        let leaderboard_chain_id = *self.state.leaderboard_chain_id.get();
+        let leaderboard_move = self.state.leaderboard_move.get().clone();
+        let game_constants = self.runtime.application_parameters().game;
         
         // User management fields
-        let current_user = self.state.current_user.get().clone();
+        let current_user = self.state.current_user.get().as_ref().map(UserProfile::from);
+        let reserved_usernames = self.state.reserved_usernames.get().clone();
+
+        // Registered users, reduced to their public profile
+        let mut all_users = std::collections::HashMap::new();
+        let usernames = self.state.users.indices().await.unwrap_or_default();
+        for username in usernames {
+            if let Ok(Some(user)) = self.state.users.get(&username).await {
+                all_users.insert(username, UserProfile::from(&user));
+            }
+        }
 
         // Precompute all login results for the GraphQL query
         let mut all_login_results = std::collections::HashMap::new();
@@ -55,10 +78,106 @@ impl Service for FlappyService {
             }
         }
 
+        // Failed login tracking
+        let mut all_login_attempts = std::collections::HashMap::new();
+        let attempt_usernames = self.state.login_attempts.indices().await.unwrap_or_default();
+        for username in attempt_usernames {
+            if let Ok(Some(attempts)) = self.state.login_attempts.get(&username).await {
+                all_login_attempts.insert(username, attempts);
+            }
+        }
+
+        let mut all_chain_login_attempts = std::collections::HashMap::new();
+        let attempt_chain_ids = self
+            .state
+            .chain_login_attempts
+            .indices()
+            .await
+            .unwrap_or_default();
+        for chain_id in attempt_chain_ids {
+            if let Ok(Some(attempts)) = self.state.chain_login_attempts.get(&chain_id).await {
+                all_chain_login_attempts.insert(chain_id.to_string(), attempts);
+            }
+        }
+
+        // Account moderation, only restrictions still in force
+        let now = self.runtime.system_time().micros();
+        let mut account_restrictions = std::collections::HashMap::new();
+        let restricted_usernames = self
+            .state
+            .account_restrictions
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in restricted_usernames {
+            if let Ok(Some(restriction)) = self.state.account_restrictions.get(&username).await {
+                if restriction.is_active(now) {
+                    account_restrictions.insert(username, restriction);
+                }
+            }
+        }
+        let hidden_usernames: std::collections::HashSet<String> = account_restrictions
+            .iter()
+            .filter(|(_, restriction)| restriction.hides_scores(now))
+            .map(|(username, _)| username.clone())
+            .collect();
+
+        // Score moderation fields, oldest submission first
+        let moderation_thresholds = self.state.moderation_thresholds.get().clone();
+        let mut pending_scores = Vec::new();
+        let pending_ids = self.state.pending_scores.indices().await.unwrap_or_default();
+        for pending_id in pending_ids {
+            if let Ok(Some(pending)) = self.state.pending_scores.get(&pending_id).await {
+                pending_scores.push(pending);
+            }
+        }
+        pending_scores.sort_by_key(|pending| pending.submitted_at);
+
         // Practice mode fields
         let practice_leaderboard = self.state.practice_leaderboard.get().clone();
-        let my_practice_scores = self.state.my_practice_scores.get().clone();
         let my_practice_best = *self.state.my_practice_best.get();
+        let open_run_ticket = self.state.open_run_ticket.get().clone();
+
+        // Practice statistics
+        let mut all_practice_stats = std::collections::HashMap::new();
+        let stats_usernames = self
+            .state
+            .practice_stats
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in stats_usernames {
+            if let Ok(Some(stats)) = self.state.practice_stats.get(&username).await {
+                all_practice_stats.insert(username, stats);
+            }
+        }
+        let global_practice_stats = self.state.global_practice_stats.get().clone();
+
+        let mut all_practice_best_scores = std::collections::HashMap::new();
+        let best_usernames = self
+            .state
+            .practice_best_scores
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in best_usernames {
+            if let Ok(Some(entry)) = self.state.practice_best_scores.get(&username).await {
+                all_practice_best_scores.insert(username, entry);
+            }
+        }
+
+        let mut all_pending_practice_runs = std::collections::HashMap::new();
+        let pending_usernames = self
+            .state
+            .pending_practice_runs
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in pending_usernames {
+            if let Ok(Some(summary)) = self.state.pending_practice_runs.get(&username).await {
+                all_pending_practice_runs.insert(username, summary);
+            }
+        }
 
         // Tournament fields
         let my_tournaments = self.state.my_tournaments.get().clone();
@@ -81,30 +200,166 @@ impl Service for FlappyService {
         }
         
         // Get personal tournament scores
-        for tournament_id in &my_tournaments {
-            if let Ok(Some(scores)) = self.state.my_tournament_scores.get(tournament_id).await {
-                my_tournament_scores_map.insert(tournament_id.clone(), scores);
+        let scored_tournament_ids = self
+            .state
+            .my_tournament_scores
+            .indices()
+            .await
+            .unwrap_or_default();
+        for tournament_id in scored_tournament_ids {
+            if let Ok(Some(scores)) = self.state.my_tournament_scores.get(&tournament_id).await {
+                my_tournament_scores_map.insert(tournament_id, scores);
             }
         }
         
         let pinned_tournaments = self.state.pinned_tournaments.get().clone();
 
+        // Achievements
+        let mut all_achievements = std::collections::HashMap::new();
+        let achievement_usernames = self.state.achievements.indices().await.unwrap_or_default();
+        for username in achievement_usernames {
+            if let Ok(Some(achievements)) = self.state.achievements.get(&username).await {
+                all_achievements.insert(username, achievements);
+            }
+        }
+
+        // Friends
+        let mut all_friendships = std::collections::HashMap::new();
+        let friendship_usernames = self.state.friendships.indices().await.unwrap_or_default();
+        for username in friendship_usernames {
+            if let Ok(Some(friends)) = self.state.friendships.get(&username).await {
+                all_friendships.insert(username, friends);
+            }
+        }
+
+        let mut friends = Vec::new();
+        let friend_usernames = self.state.friends.indices().await.unwrap_or_default();
+        for username in friend_usernames {
+            if let Ok(Some(friend)) = self.state.friends.get(&username).await {
+                friends.push(friend);
+            }
+        }
+
+        let mut incoming_friend_requests = Vec::new();
+        let incoming_usernames = self
+            .state
+            .incoming_friend_requests
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in incoming_usernames {
+            if let Ok(Some(request)) = self.state.incoming_friend_requests.get(&username).await {
+                incoming_friend_requests.push(request);
+            }
+        }
+
+        let mut outgoing_friend_requests = Vec::new();
+        let outgoing_usernames = self
+            .state
+            .outgoing_friend_requests
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in outgoing_usernames {
+            if let Ok(Some(request)) = self.state.outgoing_friend_requests.get(&username).await {
+                outgoing_friend_requests.push(request);
+            }
+        }
+
+        // Challenges, preferring the leaderboard chain's authoritative copy over the local cache
+        let mut all_challenges = std::collections::HashMap::new();
+        let my_challenge_ids = self.state.my_challenges.indices().await.unwrap_or_default();
+        for challenge_id in my_challenge_ids {
+            if let Ok(Some(challenge)) = self.state.my_challenges.get(&challenge_id).await {
+                all_challenges.insert(challenge_id, challenge);
+            }
+        }
+        let challenge_ids = self.state.challenges.indices().await.unwrap_or_default();
+        for challenge_id in challenge_ids {
+            if let Ok(Some(challenge)) = self.state.challenges.get(&challenge_id).await {
+                all_challenges.insert(challenge_id, challenge);
+            }
+        }
+
+        let mut all_head_to_head = std::collections::HashMap::new();
+        let pairs = self.state.head_to_head.indices().await.unwrap_or_default();
+        for pair in pairs {
+            if let Ok(Some(record)) = self.state.head_to_head.get(&pair).await {
+                all_head_to_head.insert(pair, record);
+            }
+        }
+
+        // Ghost runs
+        let mut practice_ghosts = std::collections::HashMap::new();
+        let ghost_usernames = self
+            .state
+            .practice_ghosts
+            .indices()
+            .await
+            .unwrap_or_default();
+        for username in ghost_usernames {
+            if let Ok(Some(ghost)) = self.state.practice_ghosts.get(&username).await {
+                practice_ghosts.insert(username, ghost);
+            }
+        }
+
+        let mut tournament_ghosts = std::collections::HashMap::new();
+        let ghost_keys = self
+            .state
+            .tournament_ghosts
+            .indices()
+            .await
+            .unwrap_or_default();
+        for key in ghost_keys {
+            if let Ok(Some(ghost)) = self.state.tournament_ghosts.get(&key).await {
+                tournament_ghosts.insert(key, ghost);
+            }
+        }
+
+        let query_root = QueryRoot {
+            player_name,
+            is_leaderboard,
+            schema_version,
+            leaderboard_chain_id,
+            leaderboard_move,
+            game_constants,
+            current_user,
+            reserved_usernames,
+            all_users,
+            now,
+            all_login_results,
+            all_login_attempts,
+            all_chain_login_attempts,
+            account_restrictions,
+            hidden_usernames,
+            moderation_thresholds,
+            pending_scores,
+            practice_leaderboard,
+            state: self.state.clone(),
+            my_practice_best,
+            open_run_ticket,
+            all_practice_stats,
+            global_practice_stats,
+            all_practice_best_scores,
+            all_pending_practice_runs,
+            all_tournaments,
+            all_tournament_leaderboards,
+            my_tournaments,
+            my_tournament_scores_map,
+            pinned_tournaments,
+            all_achievements,
+            all_friendships,
+            friends,
+            incoming_friend_requests,
+            outgoing_friend_requests,
+            all_challenges,
+            all_head_to_head,
+            practice_ghosts,
+            tournament_ghosts,
+        };
+
         Schema::build(
-            QueryRoot {
-                player_name,
-                is_leaderboard,
-                leaderboard,
-                current_user,
-                all_login_results,
-                practice_leaderboard,
-                my_practice_scores,
-                my_practice_best,
-                all_tournaments,
-                all_tournament_leaderboards,
-                my_tournaments,
-                my_tournament_scores_map,
-                pinned_tournaments,
-            },
+            query_root,
             Operation::mutation_root(self.runtime.clone()),
             EmptySubscription,
         )
@@ -117,17 +372,43 @@ impl Service for FlappyService {
 struct QueryRoot {
     player_name: String,
     is_leaderboard: bool,
-    leaderboard: Vec<LeaderboardEntry>,
-    current_user: Option<User>,
+    schema_version: u32, // Below SCHEMA_VERSION while a migration is running
+    leaderboard_chain_id: Option<ChainId>,
+    leaderboard_move: Option<LeaderboardMove>, // Set on a leaderboard chain that started moving
+    game_constants: GameConstants,
+    current_user: Option<UserProfile>,
+    reserved_usernames: Vec<String>,
+    all_users: std::collections::HashMap<String, UserProfile>,
+    now: u64, // Local time of the query
     all_login_results: std::collections::HashMap<String, LoginResult>,
+    all_login_attempts: std::collections::HashMap<String, LoginAttempts>,
+    all_chain_login_attempts: std::collections::HashMap<String, LoginAttempts>,
+    account_restrictions: std::collections::HashMap<String, AccountRestriction>,
+    hidden_usernames: std::collections::HashSet<String>, // Scores left out of leaderboards
+    moderation_thresholds: ModerationThresholds,
+    pending_scores: Vec<PendingScore>, // Oldest first
     practice_leaderboard: Vec<PracticeEntry>,
-    my_practice_scores: Vec<u64>,
+    state: Arc<FlappyState>, // Read lazily by queries that page through views
     my_practice_best: u64,
+    open_run_ticket: Option<RunTicket>,
+    all_practice_stats: std::collections::HashMap<String, PracticeStats>,
+    global_practice_stats: PracticeStats,
+    all_practice_best_scores: std::collections::HashMap<String, PracticeEntry>,
+    all_pending_practice_runs: std::collections::HashMap<String, PracticeRunsSummary>,
     all_tournaments: std::collections::HashMap<String, Tournament>,
     all_tournament_leaderboards: std::collections::HashMap<String, Vec<TournamentResult>>,
     my_tournaments: Vec<String>,
     my_tournament_scores_map: std::collections::HashMap<String, Vec<u64>>,
     pinned_tournaments: Vec<String>,
+    all_achievements: std::collections::HashMap<String, Vec<Achievement>>,
+    all_friendships: std::collections::HashMap<String, Vec<String>>,
+    friends: Vec<Friend>,
+    incoming_friend_requests: Vec<FriendRequest>,
+    outgoing_friend_requests: Vec<FriendRequest>,
+    all_challenges: std::collections::HashMap<String, Challenge>,
+    all_head_to_head: std::collections::HashMap<(String, String), HeadToHead>,
+    practice_ghosts: std::collections::HashMap<String, GhostRun>,
+    tournament_ghosts: std::collections::HashMap<(String, String), GhostRun>,
 }
 
 #[Object]
@@ -140,19 +421,42 @@ impl QueryRoot {
         self.is_leaderboard
     }
 
-    async fn leaderboard(&self) -> &Vec<LeaderboardEntry> {
-        &self.leaderboard
+    async fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    // Clients follow a moved leaderboard chain through this
+    async fn leaderboard_chain_id(&self) -> Option<ChainId> {
+        self.leaderboard_chain_id
+    }
+
+    async fn leaderboard_move(&self) -> Option<&LeaderboardMove> {
+        self.leaderboard_move.as_ref()
+    }
+
+    async fn game_constants(&self) -> &GameConstants {
+        &self.game_constants
     }
 
+    // Practice rank of the user logged in on this chain, from the replicated top 100 on player chains
     async fn my_rank(&self) -> Option<usize> {
-        self.leaderboard
+        let username = &self.current_user.as_ref()?.username;
+        self.practice_leaderboard
             .iter()
-            .position(|entry| entry.player_name == self.player_name)
+            .position(|entry| &entry.username == username)
             .map(|pos| pos + 1)
     }
 
+    async fn my_tournament_rank(&self, tournament_id: String) -> Option<u32> {
+        let username = &self.current_user.as_ref()?.username;
+        self.visible_tournament_leaderboard(&tournament_id)?
+            .iter()
+            .find(|result| &result.username == username)
+            .map(|result| result.rank)
+    }
+
     // User management queries
-    async fn current_user(&self) -> &Option<User> {
+    async fn current_user(&self) -> &Option<UserProfile> {
         &self.current_user
     }
 
@@ -176,19 +480,96 @@ impl QueryRoot {
         self.current_user.as_ref().map(|u| &u.username)
     }
 
+    async fn reserved_usernames(&self) -> &Vec<String> {
+        &self.reserved_usernames
+    }
+
+    async fn login_attempts(&self, username: String) -> Option<LoginAttempts> {
+        self.all_login_attempts.get(&username).cloned()
+    }
+
+    async fn chain_login_attempts(&self, chain_id: String) -> Option<LoginAttempts> {
+        self.all_chain_login_attempts.get(&chain_id).cloned()
+    }
+
+    async fn account_restriction(&self, username: String) -> Option<AccountRestriction> {
+        self.account_restrictions.get(&username).cloned()
+    }
+
+    // Score moderation queries
+    async fn moderation_thresholds(&self) -> &ModerationThresholds {
+        &self.moderation_thresholds
+    }
+
+    async fn pending_scores(&self) -> &Vec<PendingScore> {
+        &self.pending_scores
+    }
+
     // Practice mode queries
     async fn practice_leaderboard(&self) -> &Vec<PracticeEntry> {
         &self.practice_leaderboard
     }
 
-    async fn my_practice_scores(&self) -> &Vec<u64> {
-        &self.my_practice_scores
+    async fn my_practice_scores(&self) -> Vec<u64> {
+        self.practice_history()
+            .await
+            .iter()
+            .map(|run| run.score)
+            .collect()
+    }
+
+    async fn my_practice_history(
+        &self,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> PracticeHistoryPage {
+        let offset = offset.unwrap_or(0) as usize;
+        let limit = limit.unwrap_or(50).min(500) as usize;
+        let queued = self.state.my_practice_history.count();
+        let legacy = self.state.my_practice_scores.get();
+        let total_count = queued + legacy.len();
+
+        // Newest first: only the requested end of the queue is read, then the legacy
+        // runs that predate it
+        let mut runs: Vec<PracticeRun> = self
+            .state
+            .my_practice_history
+            .read_back((offset + limit).min(queued))
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .rev()
+            .skip(offset)
+            .collect();
+        if runs.len() < limit {
+            runs.extend(
+                legacy
+                    .iter()
+                    .rev()
+                    .skip(offset.saturating_sub(queued))
+                    .take(limit - runs.len())
+                    .map(|score| PracticeRun {
+                        score: *score,
+                        timestamp: 0,
+                    }),
+            );
+        }
+
+        PracticeHistoryPage {
+            has_more: offset + runs.len() < total_count,
+            runs,
+            total_count: total_count as u64,
+        }
     }
 
     async fn my_practice_best(&self) -> u64 {
         self.my_practice_best
     }
 
+    async fn open_run_ticket(&self) -> &Option<RunTicket> {
+        &self.open_run_ticket
+    }
+
     async fn practice_best_score(&self, username: String) -> Option<PracticeEntry> {
         self.practice_leaderboard
             .iter()
@@ -207,6 +588,248 @@ impl QueryRoot {
         self.practice_leaderboard.len()
     }
 
+    // Practice statistics queries
+    async fn practice_stats(&self, username: String) -> Option<PracticeStats> {
+        self.all_practice_stats.get(&username).cloned()
+    }
+
+    async fn global_practice_stats(&self) -> &PracticeStats {
+        &self.global_practice_stats
+    }
+
+    async fn practice_histogram_bounds(&self) -> Vec<u64> {
+        PRACTICE_HISTOGRAM_BOUNDS.to_vec()
+    }
+
+    async fn pending_practice_runs(&self, username: String) -> Option<PracticeRunsSummary> {
+        self.all_pending_practice_runs.get(&username).cloned()
+    }
+
+    // Player profile queries
+    async fn player_profile(&self, username: String) -> PlayerProfile {
+        let mut profile = PlayerProfile {
+            username: username.clone(),
+            ..PlayerProfile::default()
+        };
+
+        // Practice figures: the leaderboard chain only has batched statistics, so percentiles
+        // are estimated from the histogram; player chains keep the exact run history
+        if self.is_leaderboard {
+            if let Some(stats) = self.all_practice_stats.get(&username) {
+                profile.games_played = stats.total_runs;
+                if stats.total_runs > 0 {
+                    profile.mean_score = stats.total_score as f64 / stats.total_runs as f64;
+                }
+                profile.median_score = histogram_percentile(&stats.buckets, 50);
+                profile.p25_score = histogram_percentile(&stats.buckets, 25);
+                profile.p75_score = histogram_percentile(&stats.buckets, 75);
+                profile.p90_score = histogram_percentile(&stats.buckets, 90);
+            }
+            if let Some(entry) = self.all_practice_best_scores.get(&username) {
+                profile.best_score = entry.score;
+            }
+        } else if username == self.player_name {
+            let mut scores: Vec<u64> = self
+                .practice_history()
+                .await
+                .iter()
+                .map(|run| run.score)
+                .collect();
+            scores.sort_unstable();
+            profile.games_played = scores.len() as u64;
+            if !scores.is_empty() {
+                profile.mean_score = scores.iter().sum::<u64>() as f64 / scores.len() as f64;
+            }
+            profile.median_score = score_percentile(&scores, 50);
+            profile.p25_score = score_percentile(&scores, 25);
+            profile.p75_score = score_percentile(&scores, 75);
+            profile.p90_score = score_percentile(&scores, 90);
+            profile.exact_percentiles = true;
+            profile.best_score = self.my_practice_best;
+        } else if let Some(entry) = self
+            .practice_leaderboard
+            .iter()
+            .find(|entry| entry.username == username)
+        {
+            // Other players' bests are only known from the replicated top 100
+            profile.best_score = entry.score;
+        }
+        profile.practice_rank = self
+            .practice_leaderboard
+            .iter()
+            .position(|entry| entry.username == username)
+            .map(|pos| (pos + 1) as u32);
+
+        let placements = self.tournament_placements(&username);
+
+        let wins: Vec<bool> = placements
+            .iter()
+            .filter(|placement| placement.status == Some(TournamentStatus::Ended))
+            .map(|placement| placement.rank == Some(1))
+            .collect();
+        let (current_win_streak, longest_win_streak) = win_streaks(&wins);
+
+        profile.tournaments_joined = placements.len() as u64;
+        profile.tournament_wins = wins.iter().filter(|won| **won).count() as u64;
+        profile.current_win_streak = current_win_streak;
+        profile.longest_win_streak = longest_win_streak;
+        profile.tournament_placements = placements;
+        profile
+    }
+
+    // Account data export
+    async fn export_user_data(&self, username: String) -> Option<Json<UserDataExport>> {
+        // Only the user logged in on this chain can export their data
+        if self
+            .current_user
+            .as_ref()
+            .is_none_or(|user| user.username != username)
+        {
+            return None;
+        }
+
+        // Runs are only kept on the player's own chain
+        let is_own_chain = !self.is_leaderboard && username == self.player_name;
+
+        let mut challenges: Vec<Challenge> = self
+            .all_challenges
+            .values()
+            .filter(|challenge| challenge.is_participant(&username))
+            .cloned()
+            .collect();
+        challenges.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Some(Json(UserDataExport {
+            exported_at: self.now,
+            user: self.all_users.get(&username).cloned().or_else(|| {
+                self.current_user
+                    .clone()
+                    .filter(|user| user.username == username)
+            }),
+            restriction: self.account_restrictions.get(&username).cloned(),
+            practice_best: self.all_practice_best_scores.get(&username).cloned(),
+            practice_stats: self.all_practice_stats.get(&username).cloned(),
+            practice_history: if is_own_chain {
+                self.practice_history().await
+            } else {
+                Vec::new()
+            },
+            achievements: self
+                .all_achievements
+                .get(&username)
+                .cloned()
+                .unwrap_or_default(),
+            friends: self
+                .all_friendships
+                .get(&username)
+                .cloned()
+                .unwrap_or_default(),
+            tournaments: self.tournament_placements(&username),
+            tournament_scores: if is_own_chain {
+                self.my_tournament_scores_map
+                    .iter()
+                    .map(|(id, scores)| (id.clone(), scores.clone()))
+                    .collect()
+            } else {
+                Default::default()
+            },
+            challenges,
+            username,
+        }))
+    }
+
+    // Achievement queries
+    async fn achievements(&self, username: String) -> Vec<Achievement> {
+        self.all_achievements
+            .get(&username)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    // Friends queries
+    async fn friends(&self) -> &Vec<Friend> {
+        &self.friends
+    }
+
+    async fn incoming_friend_requests(&self) -> &Vec<FriendRequest> {
+        &self.incoming_friend_requests
+    }
+
+    async fn outgoing_friend_requests(&self) -> &Vec<FriendRequest> {
+        &self.outgoing_friend_requests
+    }
+
+    async fn friends_of(&self, username: String) -> Vec<String> {
+        self.all_friendships
+            .get(&username)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    async fn friends_leaderboard(&self, username: String) -> Vec<PracticeEntry> {
+        let mut entries: Vec<PracticeEntry> = self
+            .all_friendships
+            .get(&username)
+            .into_iter()
+            .flatten()
+            .chain(std::iter::once(&username))
+            .filter(|name| !self.hidden_usernames.contains(*name))
+            .filter_map(|name| self.all_practice_best_scores.get(name).cloned())
+            .collect();
+
+        // Sort by score descending (highest first)
+        entries.sort_by(|a, b| b.score.cmp(&a.score));
+        entries
+    }
+
+    // Challenge queries
+    async fn challenge(&self, id: String) -> Option<Challenge> {
+        self.all_challenges.get(&id).cloned()
+    }
+
+    async fn challenges(&self, username: String) -> Vec<Challenge> {
+        let mut challenges: Vec<Challenge> = self
+            .all_challenges
+            .values()
+            .filter(|challenge| challenge.is_participant(&username))
+            .cloned()
+            .collect();
+
+        // Newest first
+        challenges.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        challenges
+    }
+
+    async fn head_to_head(&self, player_a: String, player_b: String) -> HeadToHead {
+        let key = head_to_head_key(&player_a, &player_b);
+        self.all_head_to_head
+            .get(&key)
+            .cloned()
+            .unwrap_or_else(|| HeadToHead {
+                player_a: key.0,
+                player_b: key.1,
+                ..HeadToHead::default()
+            })
+    }
+
+    // Ghost run queries
+    async fn ghost_run(&self, username: String, context: String) -> Option<GhostReplay> {
+        let ghost = if context == PRACTICE_GHOST_CONTEXT {
+            self.practice_ghosts.get(&username)?
+        } else {
+            let key = (context.clone(), username.clone());
+            self.tournament_ghosts.get(&key)?
+        };
+
+        Some(GhostReplay {
+            username,
+            context,
+            score: ghost.score,
+            tap_frames: decode_ghost_frames(&ghost.data).ok()?,
+            recorded_at: ghost.recorded_at,
+        })
+    }
+
     // Tournament management queries
     async fn tournaments(&self) -> Vec<Tournament> {
         let mut tournaments: Vec<Tournament> = self.all_tournaments.values().cloned().collect();
@@ -257,7 +880,7 @@ impl QueryRoot {
 
     // Tournament scoring queries
     async fn tournament_leaderboard(&self, tournament_id: String) -> Option<Vec<TournamentResult>> {
-        self.all_tournament_leaderboards.get(&tournament_id).cloned()
+        self.visible_tournament_leaderboard(&tournament_id)
     }
 
     async fn my_tournament_scores(&self, tournament_id: String) -> Option<Vec<u64>> {
@@ -271,8 +894,7 @@ impl QueryRoot {
     }
 
     async fn tournament_rank(&self, tournament_id: String, username: String) -> Option<u32> {
-        self.all_tournament_leaderboards
-            .get(&tournament_id)?
+        self.visible_tournament_leaderboard(&tournament_id)?
             .iter()
             .find(|result| result.username == username)
             .map(|result| result.rank)
@@ -291,3 +913,97 @@ impl QueryRoot {
             .unwrap_or(false)
     }
 }
+
+impl QueryRoot {
+    // Whole practice history of this chain, oldest first. Runs from the legacy register
+    // haven't been moved into the bounded history yet
+    async fn practice_history(&self) -> Vec<PracticeRun> {
+        let mut history: Vec<PracticeRun> = self
+            .state
+            .my_practice_scores
+            .get()
+            .iter()
+            .map(|score| PracticeRun {
+                score: *score,
+                timestamp: 0,
+            })
+            .collect();
+        history.extend(
+            self.state
+                .my_practice_history
+                .elements()
+                .await
+                .unwrap_or_default(),
+        );
+        history
+    }
+
+    // Live leaderboard without users whose scores are hidden, ranked again
+    fn visible_tournament_leaderboard(&self, tournament_id: &str) -> Option<Vec<TournamentResult>> {
+        let mut leaderboard = self.all_tournament_leaderboards.get(tournament_id)?.clone();
+        if leaderboard
+            .iter()
+            .any(|result| self.hidden_usernames.contains(&result.username))
+        {
+            leaderboard.retain(|result| !self.hidden_usernames.contains(&result.username));
+            rank_tournament_results(&mut leaderboard);
+        }
+        Some(leaderboard)
+    }
+
+    // Tournaments the player joined, plus any they submitted scores to from this chain
+    fn tournament_placements(&self, username: &str) -> Vec<TournamentPlacement> {
+        let mut tournament_ids: Vec<String> = self
+            .all_tournaments
+            .values()
+            .filter(|tournament| tournament.participants.iter().any(|p| p == username))
+            .map(|tournament| tournament.id.clone())
+            .collect();
+        if !self.is_leaderboard && username == self.player_name {
+            for tournament_id in self.my_tournament_scores_map.keys() {
+                if !tournament_ids.contains(tournament_id) {
+                    tournament_ids.push(tournament_id.clone());
+                }
+            }
+        }
+
+        let mut placements: Vec<TournamentPlacement> = tournament_ids
+            .into_iter()
+            .map(|tournament_id| self.tournament_placement(tournament_id, username))
+            .collect();
+        placements.sort_by_key(|placement| placement.ended_at.unwrap_or(u64::MAX));
+        placements
+    }
+
+    fn tournament_placement(&self, tournament_id: String, username: &str) -> TournamentPlacement {
+        let tournament = self.all_tournaments.get(&tournament_id);
+
+        // Final results take precedence over the live leaderboard once a tournament ended
+        let result = tournament
+            .filter(|tournament| tournament.status == TournamentStatus::Ended)
+            .and_then(|tournament| tournament.results.iter().find(|r| r.username == username))
+            .cloned()
+            .or_else(|| {
+                self.visible_tournament_leaderboard(&tournament_id)?
+                    .into_iter()
+                    .find(|r| r.username == username)
+            });
+
+        let best_score = result.as_ref().map(|r| r.score).or_else(|| {
+            self.my_tournament_scores_map
+                .get(&tournament_id)
+                .and_then(|scores| scores.iter().max().copied())
+        });
+
+        TournamentPlacement {
+            tournament_name: tournament.map(|t| t.name.clone()),
+            status: tournament.map(|t| t.status),
+            best_score,
+            rank: result.map(|r| r.rank),
+            ended_at: tournament
+                .filter(|t| t.status == TournamentStatus::Ended)
+                .and_then(|t| t.end_time),
+            tournament_id,
+        }
+    }
+}
@@ -1,14 +1,22 @@
-use flappy::{LeaderboardEntry, User, LoginResult, PracticeEntry, Tournament, TournamentResult};
+use flappy::{
+    AccountRestriction, Achievement, Challenge, Credential, CredentialReset, FlappyMessage,
+    Friend, FriendRequest, GhostRun, HeadToHead, LeaderboardEntry, LeaderboardMove,
+    LoginAttempts, LoginResult, ModerationThresholds, PendingScore, PracticeEntry, PracticeRun, PracticeRunsSummary,
+    PracticeStats, RunTicket, Tournament, TournamentResult, User,
+};
 use linera_sdk::linera_base_types::ChainId;
-use linera_sdk::views::{linera_views, MapView, RegisterView, RootView, ViewStorageContext};
+use linera_sdk::views::{
+    linera_views, MapView, QueueView, RegisterView, RootView, ViewStorageContext,
+};
 
 #[derive(RootView)]
 #[view(context = ViewStorageContext)]
 pub struct FlappyState {
-    // Tournament leaderboard fields (keep for Feature 3)
     pub player_name: RegisterView<String>,
-    pub player_scores: MapView<String, LeaderboardEntry>, // For leaderboard chain: all player scores
-    pub top_leaderboard: RegisterView<Vec<LeaderboardEntry>>, // Top 10 cached leaderboard
+    // Legacy high score board, only read to migrate old deployments into practice bests.
+    // Views are stored by position, so these stay in place
+    pub legacy_player_scores: MapView<String, LeaderboardEntry>,
+    pub legacy_top_leaderboard: RegisterView<Vec<LeaderboardEntry>>,
     pub is_leaderboard_chain: RegisterView<bool>, // Flag to identify if this is the leaderboard chain
     pub leaderboard_chain_id: RegisterView<Option<ChainId>>, // Store the leaderboard chain ID
     
@@ -23,7 +31,7 @@ pub struct FlappyState {
     pub practice_best_scores: MapView<String, PracticeEntry>, // username -> best practice score
     
     // For player chains:
-    pub my_practice_scores: RegisterView<Vec<u64>>, // Personal practice score history
+    pub my_practice_scores: RegisterView<Vec<u64>>, // Legacy unbounded history, moved into my_practice_history
     pub my_practice_best: RegisterView<u64>, // Personal best practice score
     
     // Tournament management fields
@@ -36,4 +44,94 @@ pub struct FlappyState {
     // For player chains:
     pub my_tournaments: RegisterView<Vec<String>>, // Tournament IDs user has joined
     pub my_tournament_scores: MapView<String, Vec<u64>>, // tournament_id -> personal scores in that tournament
+
+    // Practice statistics fields
+    // For leaderboard chain:
+    pub practice_stats: MapView<String, PracticeStats>, // username -> aggregated practice runs
+    pub global_practice_stats: RegisterView<PracticeStats>, // Aggregated practice runs of all players
+
+    // For player chains:
+    pub pending_practice_runs: MapView<String, PracticeRunsSummary>, // username -> runs not yet reported
+
+    // Practice history fields (player chains)
+    pub my_practice_history: QueueView<PracticeRun>, // Most recent runs, oldest at the front
+    pub practice_history_cap: RegisterView<u64>, // Max runs kept in my_practice_history (0 = default)
+
+    // Achievement fields (leaderboard chain)
+    pub achievements: MapView<String, Vec<Achievement>>, // username -> unlocked achievements
+
+    // Friends fields
+    // For leaderboard chain:
+    pub friendships: MapView<String, Vec<String>>, // username -> confirmed friends
+
+    // For player chains:
+    pub friends: MapView<String, Friend>, // friend username -> Friend
+    pub incoming_friend_requests: MapView<String, FriendRequest>, // from username -> request
+    pub outgoing_friend_requests: MapView<String, FriendRequest>, // to username -> request
+
+    // Challenge fields
+    // For leaderboard chain:
+    pub challenges: MapView<String, Challenge>, // challenge_id -> Challenge
+    pub challenge_counter: RegisterView<u64>, // For generating unique challenge IDs
+    pub head_to_head: MapView<(String, String), HeadToHead>, // sorted pair -> record
+
+    // For player chains:
+    pub my_challenges: MapView<String, Challenge>, // Local cache of own challenges
+
+    // Ghost run fields
+    pub practice_ghosts: MapView<String, GhostRun>, // username -> best practice run
+    pub tournament_ghosts: MapView<(String, String), GhostRun>, // (tournament_id, username) -> best run
+
+    // Username validation fields
+    pub reserved_usernames: RegisterView<Vec<String>>, // Admin-editable names nobody can register
+
+    // Credential recovery fields
+    pub credential_resets: MapView<String, CredentialReset>, // username -> pending reset token
+
+    // Salted credential fields
+    pub credentials: MapView<String, Credential>, // username -> salted credential hash
+
+    // Login rate limiting fields
+    pub login_attempts: MapView<String, LoginAttempts>, // username -> failed logins
+    pub chain_login_attempts: MapView<ChainId, LoginAttempts>, // requester chain -> failed logins
+
+    // Account moderation fields (leaderboard chain)
+    pub account_restrictions: MapView<String, AccountRestriction>, // username -> ban, suspension or deletion
+
+    // Score moderation fields (leaderboard chain)
+    pub moderation_thresholds: RegisterView<ModerationThresholds>, // Limits for flagging scores
+    pub pending_scores: MapView<String, PendingScore>, // pending_id -> flagged score
+    pub pending_score_counter: RegisterView<u64>, // For generating unique pending score IDs
+
+    // Submission rate limiting fields
+    pub last_submission_at: RegisterView<Option<u64>>, // Player chains: time of the latest run submitted
+    pub chain_last_submissions: MapView<ChainId, u64>, // Leaderboard chain: player chain -> latest accepted submission
+
+    // Run ticket fields
+    pub run_ticket_counter: RegisterView<u64>, // Player chains: for generating ticket IDs
+    pub open_run_ticket: RegisterView<Option<RunTicket>>, // Player chains: ticket of the run in progress
+    pub chain_last_tickets: MapView<ChainId, u64>, // Leaderboard chain: player chain -> latest ticket ID used
+
+    // Schema migration fields
+    pub schema_version: RegisterView<u32>, // 0 for deployments from before versioning
+    pub migration_cursor: RegisterView<Option<String>>, // Last key the running step upgraded
+
+    // Leaderboard chain move fields (old leaderboard chain)
+    pub leaderboard_move: RegisterView<Option<LeaderboardMove>>, // Move to a new leaderboard chain, if started
+    pub held_messages: QueueView<FlappyMessage>, // Arrived during the move, passed on once it completes
+
+    // Achievement counters (leaderboard chain)
+    pub tournaments_entered: MapView<String, u64>, // username -> tournaments joined
+
+    // Username lookup fields (leaderboard chain)
+    pub normalized_usernames: MapView<String, String>, // lowercased username -> registered username
+
+    // Hidden score fields (leaderboard chain)
+    pub hidden_tournament_results: MapView<String, Vec<(String, TournamentResult)>>, // username -> (tournament_id, result) taken off live boards
+
+    // Leaderboard chain move fields (new leaderboard chain)
+    pub previous_leaderboard_chain_id: RegisterView<Option<ChainId>>, // Former leaderboard chain passing on messages
+
+    // Admin credential reset fields (leaderboard chain)
+    pub pending_admin_resets: MapView<String, CredentialReset>, // admin username -> reset waiting for a second admin key
 }
@@ -5,8 +5,112 @@
 
 #![cfg(not(target_arch = "wasm32"))]
 
-use flappy::{Operation, InstantiationArgument};
-use linera_sdk::test::{QueryOutcome, TestValidator};
+use flappy::{
+    ApplicationParameters, FlappyEvent, GameConstants, InstantiationArgument, Operation, TournamentStatus,
+    FLAPPY_STREAM_NAME,
+};
+use linera_chain::types::ConfirmedBlockCertificate;
+use linera_sdk::linera_base_types::{AccountOwner, ApplicationId, Timestamp};
+use linera_sdk::test::{ActiveChain, QueryOutcome, TestValidator};
+
+/// Plays a practice run on `chain`: takes a run ticket at `start` and submits `score` as soon as
+/// the run could have lasted that long, returning the certificate of the submission block
+async fn play_practice_run(
+    chain: &ActiveChain,
+    application_id: ApplicationId<flappy::FlappyAbi>,
+    username: &str,
+    score: u64,
+    tap_frames: Option<Vec<u32>>,
+    start: Timestamp,
+) -> ConfirmedBlockCertificate {
+    chain
+        .add_block(|block| {
+            block.with_timestamp(start);
+            block.with_operation(application_id, Operation::StartRun);
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { openRunTicket { id } }")
+        .await;
+    let ticket_id = response["openRunTicket"]["id"]
+        .as_u64()
+        .expect("Failed to get the run ticket");
+
+    chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(
+                start.micros() + GameConstants::default().min_run_duration(score),
+            ));
+            block.with_operation(
+                application_id,
+                Operation::SubmitPracticeScore {
+                    username: username.to_string(),
+                    score,
+                    tap_frames,
+                    ticket_id,
+                },
+            );
+        })
+        .await
+}
+
+/// Handles the messages sent to `chain` by `certificates` in a block at `timestamp`
+///
+/// Stands in for `handle_received_messages` once blocks carry timestamps.
+async fn receive_messages_at(
+    chain: &ActiveChain,
+    timestamp: Timestamp,
+    certificates: &[ConfirmedBlockCertificate],
+) -> ConfirmedBlockCertificate {
+    chain
+        .add_block(|block| {
+            block.with_timestamp(timestamp);
+            for certificate in certificates {
+                block.with_messages_from(certificate);
+            }
+        })
+        .await
+}
+
+/// Logs `username` in from `chain` the way the client does: the chain's login operation messages
+/// the leaderboard chain, whose reply tells the chain how it went. Every block is at `timestamp`
+async fn login_from(
+    chain: &ActiveChain,
+    leaderboard_chain: &ActiveChain,
+    application_id: ApplicationId<flappy::FlappyAbi>,
+    username: &str,
+    hash: &str,
+    timestamp: Timestamp,
+) {
+    let request = chain
+        .add_block(|block| {
+            block.with_timestamp(timestamp);
+            block.with_operation(
+                application_id,
+                Operation::LoginOrRegister {
+                    username: username.to_string(),
+                    hash: hash.to_string(),
+                },
+            );
+        })
+        .await;
+    let reply = receive_messages_at(leaderboard_chain, timestamp, &[request]).await;
+    receive_messages_at(chain, timestamp, &[reply]).await;
+}
+
+/// Decodes the events `certificate`'s block emitted on the flappy stream
+fn flappy_events(certificate: &ConfirmedBlockCertificate) -> Vec<FlappyEvent> {
+    certificate
+        .block()
+        .body
+        .events
+        .iter()
+        .flatten()
+        .filter(|event| event.stream_id.stream_name.0 == FLAPPY_STREAM_NAME)
+        .map(|event| linera_sdk::bcs::from_bytes(&event.value).expect("Failed to decode the event"))
+        .collect()
+}
 
 /// Tests basic practice score submission
 ///
@@ -14,7 +118,7 @@ use linera_sdk::test::{QueryOutcome, TestValidator};
 #[tokio::test(flavor = "multi_thread")]
 async fn single_chain_test() {
     let (validator, module_id) =
-        TestValidator::with_current_module::<flappy::FlappyAbi, (), InstantiationArgument>().await;
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
     let mut chain = validator.new_chain().await;
 
     let args = InstantiationArgument {
@@ -23,19 +127,24 @@ async fn single_chain_test() {
         admin_hash: None,
     };
     let application_id = chain
-        .create_application(module_id, (), args, vec![])
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    // Fresh deployments start on the current schema
+    let QueryOutcome { response, .. } =
+        chain.graphql_query(application_id, "query { schemaVersion }").await;
+    assert_eq!(response["schemaVersion"], flappy::SCHEMA_VERSION);
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { gameConstants { gravity pipeSpeed } }")
         .await;
+    let defaults = GameConstants::default();
+    assert_eq!(response["gameConstants"]["gravity"], defaults.gravity);
+    assert_eq!(response["gameConstants"]["pipeSpeed"], defaults.pipe_speed);
 
     // Submit a practice score
     let score = 100u64;
-    chain
-        .add_block(|block| {
-            block.with_operation(application_id, Operation::SubmitPracticeScore { 
-                username: "test_player".to_string(),
-                score,
-            });
-        })
-        .await;
+    play_practice_run(&chain, application_id, "test_player", score, None, Timestamp::from(0)).await;
 
     let QueryOutcome { response, .. } =
         chain.graphql_query(application_id, "query { myPracticeBest }").await;
@@ -48,7 +157,7 @@ async fn single_chain_test() {
 #[tokio::test(flavor = "multi_thread")]
 async fn user_management_test() {
     let (validator, module_id) =
-        TestValidator::with_current_module::<flappy::FlappyAbi, (), InstantiationArgument>().await;
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
     let mut chain = validator.new_chain().await;
 
     let args = InstantiationArgument {
@@ -57,7 +166,7 @@ async fn user_management_test() {
         admin_hash: Some("admin_hash".to_string()),
     };
     let application_id = chain
-        .create_application(module_id, (), args, vec![])
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
         .await;
 
     // Setup as leaderboard chain
@@ -74,18 +183,7 @@ async fn user_management_test() {
         .await;
 
     // Test that admin was created during instantiation
-    chain
-        .add_block(|block| {
-            block.with_operation(
-                application_id,
-                Operation::LoginOrRegister {
-                    username: "admin".to_string(),
-                    hash: "admin_hash".to_string(),
-                    requester_chain_id: chain.id(),
-                },
-            );
-        })
-        .await;
+    login_from(&chain, &chain, application_id, "admin", "admin_hash", Timestamp::from(0)).await;
 
     // Query admin login result
     let QueryOutcome { response, .. } = chain
@@ -110,18 +208,7 @@ async fn user_management_test() {
     assert_eq!(role, "ADMIN"); // GraphQL enum returns uppercase
 
     // Test user registration
-    chain
-        .add_block(|block| {
-            block.with_operation(
-                application_id,
-                Operation::LoginOrRegister {
-                    username: "testuser".to_string(),
-                    hash: "testhash".to_string(),
-                    requester_chain_id: chain.id(),
-                },
-            );
-        })
-        .await;
+    login_from(&chain, &chain, application_id, "testuser", "testhash", Timestamp::from(0)).await;
 
     // Query the login result using the new loginResultFor query
     let QueryOutcome { response, .. } = chain
@@ -142,18 +229,7 @@ async fn user_management_test() {
     assert!(is_new_user);
 
     // Test login with same user
-    chain
-        .add_block(|block| {
-            block.with_operation(
-                application_id,
-                Operation::LoginOrRegister {
-                    username: "testuser".to_string(),
-                    hash: "testhash".to_string(),
-                    requester_chain_id: chain.id(),
-                },
-            );
-        })
-        .await;
+    login_from(&chain, &chain, application_id, "testuser", "testhash", Timestamp::from(0)).await;
 
     // Query the login result again using the new loginResultFor query
     let QueryOutcome { response, .. } = chain
@@ -172,13 +248,23 @@ async fn user_management_test() {
 
     assert!(success);
     assert!(!is_new_user); // Should be false for existing user
+
+    // Credential hashes are not part of the public user type
+    let hash_query = format!(
+        "query {{ loginResultFor(chainId: \"{}\") {{ user {{ username hash }} }} }}",
+        chain.id()
+    );
+    assert!(chain
+        .try_graphql_query(application_id, &*hash_query)
+        .await
+        .is_err());
 }
 
 /// Tests concurrent user management - simulates multiple chains registering at the same time
 #[tokio::test(flavor = "multi_thread")]
 async fn concurrent_user_test() {
     let (validator, module_id) =
-        TestValidator::with_current_module::<flappy::FlappyAbi, (), InstantiationArgument>().await;
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
     
     // Create leaderboard chain with admin
     let mut leaderboard_chain = validator.new_chain().await;
@@ -188,7 +274,7 @@ async fn concurrent_user_test() {
         admin_hash: Some("admin_hash".to_string()),
     };
     let leaderboard_app_id = leaderboard_chain
-        .create_application(module_id, (), leaderboard_args, vec![])
+        .create_application(module_id, ApplicationParameters::default(), leaderboard_args, vec![])
         .await;
 
     // Setup as leaderboard chain
@@ -205,50 +291,38 @@ async fn concurrent_user_test() {
         .await;
 
     // Create two different "user" chains
-    let mut user1_chain = validator.new_chain().await;
-    let mut user2_chain = validator.new_chain().await;
-    
-    let user1_args = InstantiationArgument {
-        player_name: "user1".to_string(),
-        admin_username: None,
-        admin_hash: None,
-    };
-    let user2_args = InstantiationArgument {
-        player_name: "user2".to_string(),
-        admin_username: None,
-        admin_hash: None,
-    };
-    
-    let _user1_app_id = user1_chain
-        .create_application(module_id, (), user1_args, vec![])
-        .await;
-    let _user2_app_id = user2_chain
-        .create_application(module_id, (), user2_args, vec![])
-        .await;
+    let user1_chain = validator.new_chain().await;
+    let user2_chain = validator.new_chain().await;
 
-    // Both users register on the leaderboard chain
-    leaderboard_chain
-        .add_block(|block| {
-            // User 1 registers
-            block.with_operation(
-                leaderboard_app_id,
-                Operation::LoginOrRegister {
-                    username: "alice".to_string(),
-                    hash: "alice_hash".to_string(),
-                    requester_chain_id: user1_chain.id(),
-                },
-            );
-            // User 2 registers (in same block)
-            block.with_operation(
-                leaderboard_app_id,
-                Operation::LoginOrRegister {
-                    username: "bob".to_string(),
-                    hash: "bob_hash".to_string(),
-                    requester_chain_id: user2_chain.id(),
-                },
-            );
-        })
-        .await;
+    // Both users ask the leaderboard chain from their own chains
+    let mut requests = Vec::new();
+    for (user_chain, username, hash) in [
+        (&user1_chain, "alice", "alice_hash"),
+        (&user2_chain, "bob", "bob_hash"),
+    ] {
+        let request = user_chain
+            .add_block(|block| {
+                block.with_operation(
+                    leaderboard_app_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+                block.with_operation(
+                    leaderboard_app_id,
+                    Operation::LoginOrRegister {
+                        username: username.to_string(),
+                        hash: hash.to_string(),
+                    },
+                );
+            })
+            .await;
+        requests.push(request);
+    }
+
+    // Both register in the same block
+    receive_messages_at(&leaderboard_chain, Timestamp::from(0), &requests).await;
 
     // Query User 1's result (should be Alice's registration)
     let QueryOutcome { response: user1_response, .. } = leaderboard_chain
@@ -289,3 +363,2148 @@ async fn concurrent_user_test() {
         println!("❌ User 2 result not found");
     }
 }
+
+/// Tests that practice runs are batched from a player chain into leaderboard chain statistics
+#[tokio::test(flavor = "multi_thread")]
+async fn practice_stats_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    // Both chains point at the leaderboard chain
+    for chain in [&leaderboard_chain, &player_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    // Runs start a minute apart
+    let minute = |n: u64| Timestamp::from(n * 60_000_000);
+    let mut certificates = Vec::new();
+
+    // The first run is a personal best and is reported right away, the rest stay pending
+    for (n, score) in [5, 3, 2].into_iter().enumerate() {
+        let certificate =
+            play_practice_run(&player_chain, application_id, "alice", score, None, minute(n as u64))
+                .await;
+        certificates.push(certificate);
+    }
+
+    let QueryOutcome { response, .. } = player_chain
+        .graphql_query(application_id, "query { pendingPracticeRuns(username: \"alice\") { count sum } }")
+        .await;
+    assert_eq!(response["pendingPracticeRuns"]["count"].as_u64(), Some(2));
+    assert_eq!(response["pendingPracticeRuns"]["sum"].as_u64(), Some(5));
+
+    // Flush the remaining runs explicitly
+    let certificate = player_chain
+        .add_block(|block| {
+            block.with_timestamp(minute(3));
+            block.with_operation(
+                application_id,
+                Operation::FlushPracticeRuns {
+                    username: "alice".to_string(),
+                },
+            );
+        })
+        .await;
+    certificates.push(certificate);
+
+    receive_messages_at(&leaderboard_chain, minute(3), &certificates).await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(
+            application_id,
+            "query { globalPracticeStats { totalRuns totalScore players } practiceStats(username: \"alice\") { totalRuns averageScore } }",
+        )
+        .await;
+
+    assert_eq!(response["globalPracticeStats"]["totalRuns"].as_u64(), Some(3));
+    assert_eq!(response["globalPracticeStats"]["totalScore"].as_u64(), Some(10));
+    assert_eq!(response["globalPracticeStats"]["players"].as_u64(), Some(1));
+    assert_eq!(response["practiceStats"]["totalRuns"].as_u64(), Some(3));
+    let average = response["practiceStats"]["averageScore"]
+        .as_f64()
+        .expect("Failed to get averageScore");
+    assert!((average - 10.0 / 3.0).abs() < 1e-9);
+}
+
+/// Tests the player profile computed from a player chain's practice history
+#[tokio::test(flavor = "multi_thread")]
+async fn player_profile_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "test_player".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    // Runs start a minute apart
+    for (n, score) in [3, 9, 6, 12].into_iter().enumerate() {
+        let start = Timestamp::from(n as u64 * 60_000_000);
+        play_practice_run(&chain, application_id, "test_player", score, None, start).await;
+    }
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { playerProfile(username: \"test_player\") { gamesPlayed bestScore meanScore medianScore p90Score exactPercentiles tournamentsJoined } }",
+        )
+        .await;
+    let profile = &response["playerProfile"];
+
+    assert_eq!(profile["gamesPlayed"].as_u64(), Some(4));
+    assert_eq!(profile["bestScore"].as_u64(), Some(12));
+    assert_eq!(profile["meanScore"].as_f64(), Some(7.5));
+    assert_eq!(profile["medianScore"].as_u64(), Some(6));
+    assert_eq!(profile["p90Score"].as_u64(), Some(12));
+    assert_eq!(profile["exactPercentiles"].as_bool(), Some(true));
+    assert_eq!(profile["tournamentsJoined"].as_u64(), Some(0));
+}
+
+/// Tests that the practice history keeps only the most recent runs and pages newest first
+#[tokio::test(flavor = "multi_thread")]
+async fn practice_history_cap_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "test_player".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, Operation::SetPracticeHistoryCap { cap: 2 });
+        })
+        .await;
+
+    // Runs start a minute apart
+    for (n, score) in [4, 8, 15].into_iter().enumerate() {
+        let start = Timestamp::from(n as u64 * 60_000_000);
+        play_practice_run(&chain, application_id, "test_player", score, None, start).await;
+    }
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { myPracticeScores myPracticeHistory(offset: 0, limit: 1) { totalCount hasMore runs { score timestamp } } }",
+        )
+        .await;
+
+    assert_eq!(response["myPracticeScores"], serde_json::json!([8, 15]));
+    let page = &response["myPracticeHistory"];
+    assert_eq!(page["totalCount"].as_u64(), Some(2));
+    assert_eq!(page["hasMore"].as_bool(), Some(true));
+    assert_eq!(page["runs"][0]["score"].as_u64(), Some(15));
+    assert!(page["runs"][0]["timestamp"].as_u64().is_some());
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(
+            application_id,
+            "query { myPracticeHistory(offset: 1, limit: 5) { hasMore runs { score } } }",
+        )
+        .await;
+    let page = &response["myPracticeHistory"];
+    assert_eq!(page["hasMore"].as_bool(), Some(false));
+    assert_eq!(page["runs"], serde_json::json!([{ "score": 8 }]));
+}
+
+/// Tests that practice milestones unlock achievements on the leaderboard chain
+#[tokio::test(flavor = "multi_thread")]
+async fn achievements_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    let certificate =
+        play_practice_run(&player_chain, application_id, "alice", 12, None, Timestamp::from(0)).await;
+
+    receive_messages_at(&leaderboard_chain, Timestamp::from(60_000_000), &[certificate]).await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { achievements(username: \"alice\") { kind unlockedAt } }")
+        .await;
+    let kinds: Vec<&str> = response["achievements"]
+        .as_array()
+        .expect("Failed to get achievements")
+        .iter()
+        .filter_map(|achievement| achievement["kind"].as_str())
+        .collect();
+
+    assert_eq!(kinds, vec!["FIRST_RUN", "SCORE_10"]);
+
+    // Joining ten tournaments unlocks TenTournaments on the tenth
+    login_from(
+        &leaderboard_chain,
+        &leaderboard_chain,
+        application_id,
+        "admin",
+        "admin_hash",
+        Timestamp::from(60_000_000),
+    )
+    .await;
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(60_000_000));
+            for n in 0..10 {
+                block.with_operation(
+                    application_id,
+                    Operation::CreateTournament {
+                        caller_chain_id: leaderboard_chain.id(),
+                        name: format!("Cup {}", n),
+                        description: String::new(),
+                        start_time: None,
+                        end_time: None,
+                    },
+                );
+                block.with_operation(
+                    application_id,
+                    Operation::JoinTournament {
+                        tournament_id: format!("tournament_{}", n),
+                        username: "alice".to_string(),
+                    },
+                );
+            }
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { achievements(username: \"alice\") { kind } }")
+        .await;
+    assert_eq!(response["achievements"][2]["kind"], "TEN_TOURNAMENTS");
+}
+
+/// Tests friend requests routed through the leaderboard chain and the friends-only leaderboard
+#[tokio::test(flavor = "multi_thread")]
+async fn friends_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let alice_chain = validator.new_chain().await;
+    let bob_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &alice_chain, &bob_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    // Register both players so the leaderboard chain knows their chains
+    for (username, chain) in [("alice", &alice_chain), ("bob", &bob_chain)] {
+        login_from(chain, &leaderboard_chain, application_id, username, "hash", Timestamp::from(0))
+            .await;
+    }
+
+    alice_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::AddFriend {
+                    username: "alice".to_string(),
+                    friend_username: "bob".to_string(),
+                },
+            );
+        })
+        .await;
+    leaderboard_chain.handle_received_messages().await;
+    bob_chain.handle_received_messages().await;
+
+    let QueryOutcome { response, .. } = bob_chain
+        .graphql_query(application_id, "query { incomingFriendRequests { fromUsername toUsername } }")
+        .await;
+    assert_eq!(response["incomingFriendRequests"][0]["fromUsername"], "alice");
+
+    let accept_certificate = bob_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::AcceptFriend {
+                    username: "bob".to_string(),
+                    friend_username: "alice".to_string(),
+                },
+            );
+        })
+        .await;
+    let run_certificate =
+        play_practice_run(&bob_chain, application_id, "bob", 30, None, Timestamp::from(0)).await;
+
+    // Every later block must not predate bob's run
+    let later = Timestamp::from(120_000_000);
+    let certificate = receive_messages_at(
+        &leaderboard_chain,
+        later,
+        &[accept_certificate, run_certificate],
+    )
+    .await;
+    receive_messages_at(&alice_chain, later, &[certificate]).await;
+
+    let QueryOutcome { response, .. } = alice_chain
+        .graphql_query(application_id, "query { friends { username } outgoingFriendRequests { toUsername } }")
+        .await;
+    assert_eq!(response["friends"][0]["username"], "bob");
+    assert_eq!(response["outgoingFriendRequests"].as_array().map(Vec::len), Some(0));
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { friendsLeaderboard(username: \"alice\") { username score } }")
+        .await;
+    assert_eq!(response["friendsLeaderboard"][0]["username"], "bob");
+    assert_eq!(response["friendsLeaderboard"][0]["score"], 30);
+
+    // Removing the friend clears both sides
+    let certificate = alice_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::RemoveFriend {
+                    username: "alice".to_string(),
+                    friend_username: "bob".to_string(),
+                },
+            );
+        })
+        .await;
+    let certificate = receive_messages_at(&leaderboard_chain, later, &[certificate]).await;
+    receive_messages_at(&bob_chain, later, &[certificate]).await;
+
+    let QueryOutcome { response, .. } = bob_chain
+        .graphql_query(application_id, "query { friends { username } }")
+        .await;
+    assert_eq!(response["friends"].as_array().map(Vec::len), Some(0));
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { friendsOf(username: \"bob\") }")
+        .await;
+    assert_eq!(response["friendsOf"].as_array().map(Vec::len), Some(0));
+
+    // Requests in another user's name are dropped by the leaderboard chain
+    let certificate = alice_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::AddFriend {
+                    username: "bob".to_string(),
+                    friend_username: "alice".to_string(),
+                },
+            );
+        })
+        .await;
+    receive_messages_at(&leaderboard_chain, later, &[certificate]).await;
+    alice_chain.handle_received_messages().await;
+
+    let QueryOutcome { response, .. } = alice_chain
+        .graphql_query(application_id, "query { incomingFriendRequests { fromUsername } }")
+        .await;
+    assert_eq!(response["incomingFriendRequests"].as_array().map(Vec::len), Some(0));
+}
+
+/// Tests a head-to-head challenge from creation to the recorded winner
+#[tokio::test(flavor = "multi_thread")]
+async fn challenge_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let alice_chain = validator.new_chain().await;
+    let bob_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &alice_chain, &bob_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    for (username, chain) in [("alice", &alice_chain), ("bob", &bob_chain)] {
+        login_from(chain, &leaderboard_chain, application_id, username, "hash", Timestamp::from(0))
+            .await;
+    }
+
+    alice_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ChallengePlayer {
+                    username: "alice".to_string(),
+                    opponent: "bob".to_string(),
+                    seed: 42,
+                    deadline: 4_000_000_000,
+                },
+            );
+        })
+        .await;
+    leaderboard_chain.handle_received_messages().await;
+    bob_chain.handle_received_messages().await;
+
+    let QueryOutcome { response, .. } = bob_chain
+        .graphql_query(application_id, "query { challenges(username: \"bob\") { id challenger seed status } }")
+        .await;
+    let challenge = &response["challenges"][0];
+    assert_eq!(challenge["challenger"], "alice");
+    assert_eq!(challenge["seed"], 42);
+    assert_eq!(challenge["status"], "PENDING");
+    let challenge_id = challenge["id"].as_str().expect("Failed to get challenge ID").to_string();
+
+    // The challenger can't accept from their own chain on the opponent's behalf
+    alice_chain.handle_received_messages().await;
+    alice_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::AcceptChallenge {
+                    username: "bob".to_string(),
+                    challenge_id: challenge_id.clone(),
+                },
+            );
+        })
+        .await;
+    leaderboard_chain.handle_received_messages().await;
+    let status_query = format!("query {{ challenge(id: \"{}\") {{ status }} }}", challenge_id);
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, status_query)
+        .await;
+    assert_eq!(response["challenge"]["status"], "PENDING");
+
+    bob_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::AcceptChallenge {
+                    username: "bob".to_string(),
+                    challenge_id: challenge_id.clone(),
+                },
+            );
+        })
+        .await;
+    leaderboard_chain.handle_received_messages().await;
+    alice_chain.handle_received_messages().await;
+    bob_chain.handle_received_messages().await;
+
+    for (username, score, chain) in [("alice", 12, &alice_chain), ("bob", 8, &bob_chain)] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SubmitChallengeScore {
+                        username: username.to_string(),
+                        challenge_id: challenge_id.clone(),
+                        score,
+                    },
+                );
+            })
+            .await;
+    }
+    leaderboard_chain.handle_received_messages().await;
+
+    let query = format!(
+        "query {{ challenge(id: \"{}\") {{ status winner }} headToHead(playerA: \"bob\", playerB: \"alice\") {{ playerA playerAWins playerBWins draws }} }}",
+        challenge_id
+    );
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, query)
+        .await;
+    assert_eq!(response["challenge"]["status"], "COMPLETED");
+    assert_eq!(response["challenge"]["winner"], "alice");
+    assert_eq!(response["headToHead"]["playerA"], "alice");
+    assert_eq!(response["headToHead"]["playerAWins"], 1);
+    assert_eq!(response["headToHead"]["playerBWins"], 0);
+    assert_eq!(response["headToHead"]["draws"], 0);
+
+    // An unanswered challenge is settled by either party once its deadline passes
+    alice_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ChallengePlayer {
+                    username: "alice".to_string(),
+                    opponent: "bob".to_string(),
+                    seed: 7,
+                    deadline: 60,
+                },
+            );
+        })
+        .await;
+    leaderboard_chain.handle_received_messages().await;
+    bob_chain.handle_received_messages().await;
+
+    let later = Timestamp::from(120_000_000);
+    let resolve = |username: &str| Operation::ResolveChallenge {
+        username: username.to_string(),
+        challenge_id: "challenge_1".to_string(),
+    };
+    let result = bob_chain
+        .try_add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(application_id, resolve("carol"));
+        })
+        .await;
+    assert!(result.is_err());
+
+    let certificate = bob_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(application_id, resolve("bob"));
+        })
+        .await;
+    receive_messages_at(&leaderboard_chain, later, &[certificate]).await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { challenge(id: \"challenge_1\") { status } }")
+        .await;
+    assert_eq!(response["challenge"]["status"], "EXPIRED");
+
+    // Owning the leaderboard chain isn't enough for what only an admin key may do
+    let result = leaderboard_chain
+        .try_add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(application_id, resolve("bob"));
+        })
+        .await;
+    assert!(result.is_err());
+    let result = leaderboard_chain
+        .try_add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::MoveLeaderboardChain {
+                    new_leaderboard_chain_id: bob_chain.id(),
+                },
+            );
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+/// Tests that the tap frames of a best practice run are kept as a ghost run
+#[tokio::test(flavor = "multi_thread")]
+async fn ghost_run_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    // Only the best run keeps its ghost, runs start a minute apart
+    let mut certificates = Vec::new();
+    for (n, (score, tap_frames)) in [(3, vec![20, 60, 95, 130]), (2, vec![25, 70])]
+        .into_iter()
+        .enumerate()
+    {
+        let start = Timestamp::from(n as u64 * 60_000_000);
+        let certificate =
+            play_practice_run(&player_chain, application_id, "alice", score, Some(tap_frames), start)
+                .await;
+        certificates.push(certificate);
+    }
+    receive_messages_at(&leaderboard_chain, Timestamp::from(120_000_000), &certificates).await;
+
+    let query = "query { ghostRun(username: \"alice\", context: \"practice\") { score tapFrames } }";
+    for chain in [&player_chain, &leaderboard_chain] {
+        let QueryOutcome { response, .. } = chain.graphql_query(application_id, query).await;
+        assert_eq!(response["ghostRun"]["score"], 3);
+        assert_eq!(
+            response["ghostRun"]["tapFrames"],
+            serde_json::json!([20, 60, 95, 130])
+        );
+    }
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { ghostRun(username: \"alice\", context: \"tournament_0\") { score } }")
+        .await;
+    assert!(response["ghostRun"].is_null());
+}
+
+/// Tests that registration rejects malformed, reserved and case-colliding usernames
+#[tokio::test(flavor = "multi_thread")]
+async fn username_validation_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(chain.id()),
+        ..ApplicationParameters::default()
+    };
+    let application_id = chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    // Log in as admin on the leaderboard chain and reserve an extra name
+    login_from(&chain, &chain, application_id, "admin", "admin_hash", Timestamp::from(0)).await;
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ReserveUsername {
+                    caller_chain_id: chain.id(),
+                    username: "Flappy".to_string(),
+                    reserved: true,
+                },
+            );
+        })
+        .await;
+
+    let attempts = [
+        ("alice", true, "User registered successfully"),
+        ("ab", false, "between"),
+        ("bad name", false, "may only contain"),
+        ("Adm1n", false, "reserved"),
+        ("FL4PPY", false, "reserved"),
+        ("ALICE", false, "already taken"),
+    ];
+    for (username, expected_success, expected_message) in attempts {
+        login_from(&player_chain, &chain, application_id, username, "hash", Timestamp::from(0))
+            .await;
+
+        let QueryOutcome { response, .. } = chain
+            .graphql_query(
+                application_id,
+                &format!("query {{ loginResultFor(chainId: \"{}\") {{ success message }} }}", player_chain.id()),
+            )
+            .await;
+        let result = &response["loginResultFor"];
+        assert_eq!(result["success"], expected_success, "{}", username);
+        assert!(
+            result["message"]
+                .as_str()
+                .expect("Failed to get message")
+                .contains(expected_message),
+            "{}: {}",
+            username,
+            result["message"]
+        );
+    }
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { reservedUsernames }")
+        .await;
+    assert!(response["reservedUsernames"]
+        .as_array()
+        .expect("Failed to get reserved usernames")
+        .contains(&serde_json::json!("flappy")));
+}
+
+/// Tests changing a credential and the admin-issued one-time reset flow
+#[tokio::test(flavor = "multi_thread")]
+async fn credential_change_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(chain.id()),
+        admin_owners: vec![AccountOwner::from(chain.public_key())],
+        ..ApplicationParameters::default()
+    };
+    let application_id = chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    login_from(&player_chain, &chain, application_id, "alice", "old_hash", Timestamp::from(0))
+        .await;
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ChangeCredential {
+                    caller_chain_id: player_chain.id(),
+                    username: "alice".to_string(),
+                    old_hash: "old_hash".to_string(),
+                    new_hash: "new_hash".to_string(),
+                },
+            );
+        })
+        .await;
+
+    // The change ends alice's session
+    let login_query = format!(
+        "query {{ loginResultFor(chainId: \"{}\") {{ success message }} }}",
+        player_chain.id()
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &*login_query).await;
+    assert!(response["loginResultFor"].is_null());
+
+    // Admin key issues a reset token, alice redeems it
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::IssueCredentialReset {
+                    username: "alice".to_string(),
+                    token_hash: flappy::hash_reset_token("reset-token"),
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::ResetCredential {
+                    username: "alice".to_string(),
+                    token: "reset-token".to_string(),
+                    new_hash: "reset_hash".to_string(),
+                },
+            );
+        })
+        .await;
+
+    // Player chains learn how their logins went from the leaderboard chain's reply
+    for (hash, expected_success) in [("new_hash", false), ("reset_hash", true)] {
+        login_from(&player_chain, &chain, application_id, "alice", hash, Timestamp::from(0)).await;
+
+        let QueryOutcome { response, .. } =
+            player_chain.graphql_query(application_id, &*login_query).await;
+        assert_eq!(response["loginResultFor"]["success"], expected_success);
+    }
+
+    // One admin key alone can't reset another admin, even issuing the token twice
+    let reset_admin = || Operation::IssueCredentialReset {
+        username: "admin".to_string(),
+        token_hash: flappy::hash_reset_token("admin-token"),
+    };
+    chain
+        .add_block(|block| {
+            block.with_operation(application_id, reset_admin());
+            block.with_operation(application_id, reset_admin());
+        })
+        .await;
+    let result = chain
+        .try_add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ResetCredential {
+                    username: "admin".to_string(),
+                    token: "admin-token".to_string(),
+                    new_hash: "taken_over".to_string(),
+                },
+            );
+        })
+        .await;
+    assert!(result.is_err());
+}
+
+/// Tests lockout after repeated failed logins and clearing it as an admin
+#[tokio::test(flavor = "multi_thread")]
+async fn login_lockout_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+    let other_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(chain.id()),
+        ..ApplicationParameters::default()
+    };
+    let application_id = chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    let login = |hash: &str| Operation::LoginOrRegister {
+        username: "alice".to_string(),
+        hash: hash.to_string(),
+    };
+
+    login_from(&player_chain, &chain, application_id, "alice", "alice_hash", Timestamp::from(0))
+        .await;
+
+    // Failures from alice's own chain lock that chain out, even with the right credential
+    let request = player_chain
+        .add_block(|block| {
+            for _ in 0..flappy::LOGIN_FREE_ATTEMPTS {
+                block.with_operation(application_id, login("wrong_hash"));
+            }
+            block.with_operation(application_id, login("alice_hash"));
+        })
+        .await;
+    let reply = receive_messages_at(&chain, Timestamp::from(0), &[request]).await;
+    receive_messages_at(&player_chain, Timestamp::from(0), &[reply]).await;
+
+    let login_query = format!(
+        "query {{ loginResultFor(chainId: \"{}\") {{ success message }} }}",
+        player_chain.id()
+    );
+    let QueryOutcome { response, .. } =
+        player_chain.graphql_query(application_id, &*login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], false);
+    assert!(response["loginResultFor"]["message"]
+        .as_str()
+        .expect("Failed to get message")
+        .starts_with("Too many failed login attempts"));
+
+    let query = format!(
+        "query {{ loginAttempts(username: \"alice\") {{ failures }} chainLoginAttempts(chainId: \"{}\") {{ failures }} }}",
+        player_chain.id()
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &*query).await;
+    assert!(response["loginAttempts"].is_null());
+    assert_eq!(
+        response["chainLoginAttempts"]["failures"],
+        flappy::LOGIN_FREE_ATTEMPTS
+    );
+
+    // An admin clears the lockout and alice can log in again
+    login_from(&chain, &chain, application_id, "admin", "admin_hash", Timestamp::from(0)).await;
+    chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::ClearLoginLockout {
+                    caller_chain_id: chain.id(),
+                    username: Some("alice".to_string()),
+                    chain_id: Some(player_chain.id()),
+                },
+            );
+        })
+        .await;
+    login_from(&player_chain, &chain, application_id, "alice", "alice_hash", Timestamp::from(0))
+        .await;
+
+    let QueryOutcome { response, .. } =
+        player_chain.graphql_query(application_id, &*login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], true);
+
+    // Guessing from another chain locks that chain and the username, but not alice's own chain
+    let request = other_chain
+        .add_block(|block| {
+            for _ in 0..flappy::LOGIN_FREE_ATTEMPTS {
+                block.with_operation(application_id, login("wrong_hash"));
+            }
+        })
+        .await;
+    receive_messages_at(&chain, Timestamp::from(0), &[request]).await;
+
+    let query = format!(
+        "query {{ loginAttempts(username: \"alice\") {{ failures }} chainLoginAttempts(chainId: \"{}\") {{ failures }} }}",
+        other_chain.id()
+    );
+    let QueryOutcome { response, .. } = chain.graphql_query(application_id, &*query).await;
+    assert_eq!(response["loginAttempts"]["failures"], flappy::LOGIN_FREE_ATTEMPTS);
+    assert_eq!(response["chainLoginAttempts"]["failures"], flappy::LOGIN_FREE_ATTEMPTS);
+
+    login_from(&player_chain, &chain, application_id, "alice", "alice_hash", Timestamp::from(0))
+        .await;
+
+    let QueryOutcome { response, .. } =
+        player_chain.graphql_query(application_id, &*login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], true);
+}
+
+/// Tests banning with hidden scores, lifting the ban and soft-deleting a user
+#[tokio::test(flavor = "multi_thread")]
+async fn user_moderation_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+    let spectator_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain, &spectator_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    for (chain, username, hash) in [
+        (&leaderboard_chain, "admin", "admin_hash"),
+        (&player_chain, "alice", "alice_hash"),
+    ] {
+        login_from(chain, &leaderboard_chain, application_id, username, hash, Timestamp::from(0))
+            .await;
+    }
+
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::CreateTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    name: "Spring Cup".to_string(),
+                    description: "Moderated".to_string(),
+                    start_time: None,
+                    end_time: None,
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::JoinTournament {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::StartTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+
+    // Later leaderboard blocks must not predate the runs
+    let later = Timestamp::from(60_000_000);
+    let practice_certificate =
+        play_practice_run(&player_chain, application_id, "alice", 7, None, Timestamp::from(0)).await;
+
+    let start = Timestamp::from(GameConstants::default().min_run_duration(7));
+    player_chain
+        .add_block(|block| {
+            block.with_timestamp(start);
+            block.with_operation(application_id, Operation::StartRun);
+        })
+        .await;
+    let tournament_certificate = player_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(
+                start.micros() + GameConstants::default().min_run_duration(5),
+            ));
+            block.with_operation(
+                application_id,
+                Operation::SubmitTournamentScore {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                    score: 5,
+                    tap_frames: None,
+                    ticket_id: 2,
+                },
+            );
+        })
+        .await;
+    receive_messages_at(
+        &leaderboard_chain,
+        later,
+        &[practice_certificate, tournament_certificate],
+    )
+    .await;
+
+    let tournament_query = "query { tournamentLeaderboard(tournamentId: \"tournament_0\") { username score rank } }";
+    spectator_chain.handle_new_events().await;
+    let QueryOutcome { response, .. } = spectator_chain.graphql_query(application_id, tournament_query).await;
+    assert_eq!(response["tournamentLeaderboard"][0]["username"], "alice");
+
+    let board_query = "query { practiceLeaderboard { username score } }";
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["username"], "alice");
+
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 7);
+
+    // Boards are snapshot queries, clients re-run them on new blocks instead of subscribing
+    let subscription = "subscription { practiceLeaderboardChanged { username score } }";
+    assert!(leaderboard_chain
+        .try_graphql_query(application_id, subscription)
+        .await
+        .is_err());
+
+    // A ban with hidden scores removes alice from the board and blocks her login
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::BanUser {
+                    caller_chain_id: leaderboard_chain.id(),
+                    username: "alice".to_string(),
+                    reason: "cheating".to_string(),
+                    duration: None,
+                    hide_scores: true,
+                },
+            );
+        })
+        .await;
+    login_from(&player_chain, &leaderboard_chain, application_id, "alice", "alice_hash", later)
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"], serde_json::json!([]));
+
+    // Replicas don't know about bans, the tournament board they receive leaves alice out
+    spectator_chain.handle_new_events().await;
+    let QueryOutcome { response, .. } = spectator_chain.graphql_query(application_id, tournament_query).await;
+    assert_eq!(response["tournamentLeaderboard"], serde_json::json!([]));
+
+    let login_query = format!(
+        "query {{ loginResultFor(chainId: \"{}\") {{ success message }} }}",
+        player_chain.id()
+    );
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, &*login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], false);
+    assert_eq!(response["loginResultFor"]["message"], "Account banned: cheating");
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(
+            application_id,
+            "query { accountRestriction(username: \"alice\") { kind reason issuedBy } }",
+        )
+        .await;
+    assert_eq!(response["accountRestriction"]["kind"], "BANNED");
+    assert_eq!(response["accountRestriction"]["issuedBy"], "admin");
+
+    // Lifting the ban brings the score back
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::UnbanUser {
+                    caller_chain_id: leaderboard_chain.id(),
+                    username: "alice".to_string(),
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["username"], "alice");
+
+    spectator_chain.handle_new_events().await;
+    let QueryOutcome { response, .. } = spectator_chain.graphql_query(application_id, tournament_query).await;
+    assert_eq!(response["tournamentLeaderboard"][0]["username"], "alice");
+    assert_eq!(response["tournamentLeaderboard"][0]["score"], 5);
+    assert_eq!(response["tournamentLeaderboard"][0]["rank"], 1);
+
+    // Deleting purges the scores and keeps the name from being registered again
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::DeleteUser {
+                    caller_chain_id: leaderboard_chain.id(),
+                    username: "alice".to_string(),
+                    purge_scores: true,
+                },
+            );
+        })
+        .await;
+    login_from(&player_chain, &leaderboard_chain, application_id, "Alice", "other_hash", later)
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"], serde_json::json!([]));
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, &*login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], false);
+    assert_eq!(
+        response["loginResultFor"]["message"],
+        "Username 'Alice' is already taken"
+    );
+}
+
+/// Tests exporting a user's data and deleting one's own account
+#[tokio::test(flavor = "multi_thread")]
+async fn account_export_and_deletion_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "alice".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    // The player chain keeps alice's name so its export includes her run history
+    for (chain, name) in [(&leaderboard_chain, "leaderboard"), (&player_chain, "alice")] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: name.to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    // alice logs in from her own chain, which learns who is logged in on it
+    let login_certificate = player_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::LoginOrRegister {
+                    username: "alice".to_string(),
+                    hash: "alice_hash".to_string(),
+                },
+            );
+        })
+        .await;
+
+    // Later leaderboard blocks must not predate the run
+    let later = Timestamp::from(60_000_000);
+    let run_certificate =
+        play_practice_run(&player_chain, application_id, "alice", 12, None, Timestamp::from(0)).await;
+    let certificate = receive_messages_at(
+        &leaderboard_chain,
+        later,
+        &[login_certificate, run_certificate],
+    )
+    .await;
+    receive_messages_at(&player_chain, later, &[certificate]).await;
+
+    // Only the user logged in on a chain can export their data there
+    let export_query = "query { exportUserData(username: \"alice\") }";
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, export_query).await;
+    assert!(response["exportUserData"].is_null());
+
+    let QueryOutcome { response, .. } = player_chain
+        .graphql_query(application_id, "query { isLoggedIn exportUserData(username: \"alice\") }")
+        .await;
+    assert_eq!(response["isLoggedIn"], true);
+    let export = &response["exportUserData"];
+    assert_eq!(export["user"]["username"], "alice");
+    assert_eq!(export["practice_history"][0]["score"], 12);
+    assert!(export["restriction"].is_null());
+    assert!(export["user"].get("hash").is_none());
+
+    let QueryOutcome { response, .. } = player_chain
+        .graphql_query(application_id, "query { exportUserData(username: \"bob\") }")
+        .await;
+    assert!(response["exportUserData"].is_null());
+
+    // Deleting takes the account's credential
+    let delete = |hash: &str| Operation::DeleteMyAccount {
+        username: "alice".to_string(),
+        hash: hash.to_string(),
+        purge_scores: false,
+    };
+    let result = leaderboard_chain
+        .try_add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(application_id, delete("wrong_hash"));
+        })
+        .await;
+    assert!(result.is_err());
+
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(application_id, delete("alice_hash"));
+        })
+        .await;
+    login_from(&player_chain, &leaderboard_chain, application_id, "alice", "alice_hash", later)
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(
+            application_id,
+            &*format!(
+                "query {{ loginResultFor(chainId: \"{}\") {{ success message }} practiceLeaderboard {{ username }} }}",
+                player_chain.id()
+            ),
+        )
+        .await;
+    assert_eq!(response["loginResultFor"]["success"], false);
+    assert_eq!(response["loginResultFor"]["message"], "Account has been deleted");
+    assert_eq!(response["practiceLeaderboard"], serde_json::json!([]));
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(
+            application_id,
+            "query { accountRestriction(username: \"alice\") { kind } }",
+        )
+        .await;
+    assert_eq!(response["accountRestriction"]["kind"], "DELETED");
+
+    // The failed login ended the session on alice's chain, so her data no longer exports there
+    let QueryOutcome { response, .. } = player_chain
+        .graphql_query(application_id, "query { isLoggedIn exportUserData(username: \"alice\") }")
+        .await;
+    assert_eq!(response["isLoggedIn"], false);
+    assert!(response["exportUserData"].is_null());
+}
+
+/// Tests holding back flagged scores until an admin approves or rejects them
+#[tokio::test(flavor = "multi_thread")]
+async fn score_moderation_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    for (chain, username, hash) in [
+        (&leaderboard_chain, "admin", "admin_hash"),
+        (&player_chain, "alice", "alice_hash"),
+    ] {
+        login_from(chain, &leaderboard_chain, application_id, username, hash, Timestamp::from(0))
+            .await;
+    }
+
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::SetModerationThresholds {
+                    caller_chain_id: leaderboard_chain.id(),
+                    thresholds: flappy::ModerationThresholds {
+                        max_score: 50,
+                        max_jump: 0,
+                        min_submission_interval: 300,
+                    },
+                },
+            );
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain
+        .graphql_query(application_id, "query { moderationThresholds { maxScore } }")
+        .await;
+    assert_eq!(response["moderationThresholds"]["maxScore"], 50);
+
+    // The second run comes two minutes after the first, within the five minute interval
+    let minutes = |n: u64| Timestamp::from(n * 60_000_000);
+    let mut certificates = Vec::new();
+    for (start, score) in [(0, 7), (2, 20), (10, 80), (20, 90)] {
+        let certificate =
+            play_practice_run(&player_chain, application_id, "alice", score, None, minutes(start))
+                .await;
+        certificates.push(certificate);
+    }
+    receive_messages_at(&leaderboard_chain, minutes(30), &certificates).await;
+
+    // Only the clean score reaches the board, the others wait for review
+    let board_query = "query { practiceLeaderboard { username score } }";
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 7);
+
+    let pending_query = "query { pendingScores { id username score tournamentId reasons } }";
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, pending_query).await;
+    let pending = response["pendingScores"].as_array().unwrap();
+    assert_eq!(pending.len(), 3);
+    assert_eq!(pending[0]["score"], 20);
+    assert_eq!(pending[0]["reasons"], serde_json::json!(["TOO_FREQUENT"]));
+    assert_eq!(pending[1]["score"], 80);
+    assert_eq!(pending[1]["tournamentId"], serde_json::Value::Null);
+    assert_eq!(pending[1]["reasons"], serde_json::json!(["SCORE_TOO_HIGH"]));
+    let approved_id = pending[1]["id"].as_str().unwrap().to_string();
+    let rejected_ids = [0, 2].map(|index| pending[index]["id"].as_str().unwrap().to_string());
+
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(minutes(30));
+            block.with_operation(
+                application_id,
+                Operation::ApproveScore {
+                    caller_chain_id: leaderboard_chain.id(),
+                    pending_id: approved_id,
+                },
+            );
+            for pending_id in rejected_ids {
+                block.with_operation(
+                    application_id,
+                    Operation::RejectScore {
+                        caller_chain_id: leaderboard_chain.id(),
+                        pending_id,
+                    },
+                );
+            }
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 80);
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, pending_query).await;
+    assert_eq!(response["pendingScores"], serde_json::json!([]));
+}
+
+/// Tests that scores need an open run ticket issued long enough ago for the claimed score
+#[tokio::test(flavor = "multi_thread")]
+async fn submission_rate_limit_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "test_player".to_string(),
+        admin_username: None,
+        admin_hash: None,
+    };
+    let application_id = chain
+        .create_application(module_id, ApplicationParameters::default(), args, vec![])
+        .await;
+
+    let submit = |score, ticket_id| Operation::SubmitPracticeScore {
+        username: "test_player".to_string(),
+        score,
+        tap_frames: None,
+        ticket_id,
+    };
+
+    // Scores cannot be submitted without a ticket
+    let result = chain
+        .try_add_block(|block| {
+            block.with_operation(application_id, submit(3, 1));
+        })
+        .await;
+    assert!(result.is_err());
+
+    play_practice_run(&chain, application_id, "test_player", 3, None, Timestamp::from(0)).await;
+
+    // The ticket is closed once used
+    let result = chain
+        .try_add_block(|block| {
+            block.with_timestamp(Timestamp::from(60_000_000));
+            block.with_operation(application_id, submit(3, 1));
+        })
+        .await;
+    assert!(result.is_err());
+
+    chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(60_000_000));
+            block.with_operation(application_id, Operation::StartRun);
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { openRunTicket { id issuedAt } }")
+        .await;
+    assert_eq!(response["openRunTicket"]["id"], 2);
+    assert_eq!(response["openRunTicket"]["issuedAt"], 60_000_000);
+
+    // A score of 5 needs at least 11 seconds of play
+    let result = chain
+        .try_add_block(|block| {
+            block.with_timestamp(Timestamp::from(70_000_000));
+            block.with_operation(application_id, submit(5, 2));
+        })
+        .await;
+    assert!(result.is_err());
+
+    chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(71_000_000));
+            block.with_operation(application_id, submit(5, 2));
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = chain
+        .graphql_query(application_id, "query { myPracticeScores openRunTicket { id } }")
+        .await;
+    assert_eq!(response["myPracticeScores"], serde_json::json!([3, 5]));
+    assert!(response["openRunTicket"].is_null());
+}
+
+/// Tests that player chains follow the leaderboard chain's events into a local replica
+#[tokio::test(flavor = "multi_thread")]
+async fn leaderboard_replica_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+    let spectator_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(leaderboard_chain.id()),
+        ..Default::default()
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain, &spectator_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    // alice also logs in on the spectator chain, a second device
+    for (chain, username, hash) in [
+        (&leaderboard_chain, "admin", "admin_hash"),
+        (&player_chain, "alice", "alice_hash"),
+        (&spectator_chain, "alice", "alice_hash"),
+    ] {
+        login_from(chain, &leaderboard_chain, application_id, username, hash, Timestamp::from(0))
+            .await;
+    }
+
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::CreateTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    name: "Spring Cup".to_string(),
+                    description: "Replicated".to_string(),
+                    start_time: None,
+                    end_time: None,
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::JoinTournament {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::StartTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+
+    let later = Timestamp::from(60_000_000);
+    let practice_certificate =
+        play_practice_run(&player_chain, application_id, "alice", 7, None, Timestamp::from(0)).await;
+
+    let start = Timestamp::from(GameConstants::default().min_run_duration(7));
+    player_chain
+        .add_block(|block| {
+            block.with_timestamp(start);
+            block.with_operation(application_id, Operation::StartRun);
+        })
+        .await;
+    let tournament_certificate = player_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(
+                start.micros() + GameConstants::default().min_run_duration(5),
+            ));
+            block.with_operation(
+                application_id,
+                Operation::SubmitTournamentScore {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                    score: 5,
+                    tap_frames: None,
+                    ticket_id: 2,
+                },
+            );
+        })
+        .await;
+    receive_messages_at(
+        &leaderboard_chain,
+        later,
+        &[practice_certificate, tournament_certificate],
+    )
+    .await;
+
+    // The spectator never talked to the leaderboard chain but sees its boards locally
+    spectator_chain.handle_new_events().await;
+
+    let board_query = "query { practiceLeaderboard { username score } }";
+    let tournaments_query = "query { tournaments { id name participants } }";
+    let QueryOutcome { response, .. } = spectator_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["username"], "alice");
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 7);
+
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, tournaments_query).await;
+    assert_eq!(response["tournaments"][0]["name"], "Spring Cup");
+    assert_eq!(response["tournaments"][0]["participants"], serde_json::json!(["alice"]));
+
+    // Ranks and profiles resolve from the replica without asking the leaderboard chain
+    let standing_query = "query { myRank myTournamentRank(tournamentId: \"tournament_0\") playerProfile(username: \"alice\") { practiceRank tournamentsJoined } }";
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, standing_query).await;
+    assert_eq!(response["myRank"], 1);
+    assert_eq!(response["myTournamentRank"], 1);
+    assert_eq!(response["playerProfile"]["practiceRank"], 1);
+    assert_eq!(response["playerProfile"]["tournamentsJoined"], 1);
+
+    // Moderation and deletions reach the replica too
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(later);
+            block.with_operation(
+                application_id,
+                Operation::BanUser {
+                    caller_chain_id: leaderboard_chain.id(),
+                    username: "alice".to_string(),
+                    reason: "cheating".to_string(),
+                    duration: None,
+                    hide_scores: true,
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::DeleteTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+    spectator_chain.handle_new_events().await;
+
+    let QueryOutcome { response, .. } = spectator_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"], serde_json::json!([]));
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, standing_query).await;
+    assert_eq!(response["myRank"], serde_json::Value::Null);
+    assert_eq!(response["myTournamentRank"], serde_json::Value::Null);
+
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, tournaments_query).await;
+    assert_eq!(response["tournaments"], serde_json::json!([]));
+}
+
+/// Tests that the leaderboard chain emits registrations, new bests and tournament status changes
+#[tokio::test(flavor = "multi_thread")]
+async fn leaderboard_events_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(leaderboard_chain.id()),
+        ..Default::default()
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    player_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::SetupGame {
+                    leaderboard_chain_id: leaderboard_chain.id(),
+                    leaderboard_name: "alice".to_string(),
+                },
+            );
+        })
+        .await;
+
+    login_from(
+        &leaderboard_chain,
+        &leaderboard_chain,
+        application_id,
+        "admin",
+        "admin_hash",
+        Timestamp::from(0),
+    )
+    .await;
+    let request = player_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::LoginOrRegister {
+                    username: "alice".to_string(),
+                    hash: "alice_hash".to_string(),
+                },
+            );
+        })
+        .await;
+    let certificate = receive_messages_at(&leaderboard_chain, Timestamp::from(0), &[request]).await;
+    receive_messages_at(&player_chain, Timestamp::from(0), &[certificate.clone()]).await;
+    let events = flappy_events(&certificate);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        FlappyEvent::UserRegistered { user } if user.username == "alice"
+    )));
+
+    let run_certificate =
+        play_practice_run(&player_chain, application_id, "alice", 7, None, Timestamp::from(0)).await;
+    let certificate =
+        receive_messages_at(&leaderboard_chain, Timestamp::from(60_000_000), &[run_certificate]).await;
+    let events = flappy_events(&certificate);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        FlappyEvent::PracticeTopEntry { entry, rank: 1 } if entry.username == "alice" && entry.score == 7
+    )));
+
+    let certificate = leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(60_000_000));
+            block.with_operation(
+                application_id,
+                Operation::CreateTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    name: "Spring Cup".to_string(),
+                    description: "Evented".to_string(),
+                    start_time: None,
+                    end_time: None,
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::StartTournament {
+                    caller_chain_id: leaderboard_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+    let events = flappy_events(&certificate);
+    assert!(events.iter().any(|event| matches!(
+        event,
+        FlappyEvent::TournamentStatusChanged { tournament_id, status: TournamentStatus::Active }
+            if tournament_id == "tournament_0"
+    )));
+}
+
+/// Tests moving the leaderboard chain
+///
+/// Users, tournaments and boards reach the new chain, a score sent during the move is held and
+/// passed on, and player chains follow the new chain.
+#[tokio::test(flavor = "multi_thread")]
+async fn leaderboard_move_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut old_chain = validator.new_chain().await;
+    let new_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+    let spectator_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        leaderboard_chain_id: Some(old_chain.id()),
+        admin_owners: vec![AccountOwner::from(old_chain.public_key())],
+        ..Default::default()
+    };
+    let application_id = old_chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    for (chain, name) in [
+        (&new_chain, "leaderboard"),
+        (&player_chain, "alice"),
+        (&spectator_chain, "bob"),
+    ] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: old_chain.id(),
+                        leaderboard_name: name.to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+
+    for (chain, username, hash) in [
+        (&old_chain, "admin", "admin_hash"),
+        (&player_chain, "alice", "alice_hash"),
+        (&spectator_chain, "bob", "bob_hash"),
+    ] {
+        login_from(chain, &old_chain, application_id, username, hash, Timestamp::from(0)).await;
+    }
+    old_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::SetModerationThresholds {
+                    caller_chain_id: old_chain.id(),
+                    thresholds: flappy::ModerationThresholds {
+                        max_score: 8,
+                        max_jump: 0,
+                        min_submission_interval: 0,
+                    },
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::CreateTournament {
+                    caller_chain_id: old_chain.id(),
+                    name: "Moving Cup".to_string(),
+                    description: "Survives the move".to_string(),
+                    start_time: None,
+                    end_time: None,
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::JoinTournament {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                },
+            );
+            block.with_operation(
+                application_id,
+                Operation::StartTournament {
+                    caller_chain_id: old_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+
+    // A clean practice run, a tournament run held for review and an open challenge
+    let practice_certificate =
+        play_practice_run(&player_chain, application_id, "alice", 5, None, Timestamp::from(0)).await;
+    let start = Timestamp::from(11_000_000);
+    player_chain
+        .add_block(|block| {
+            block.with_timestamp(start);
+            block.with_operation(application_id, Operation::StartRun);
+        })
+        .await;
+    let end = Timestamp::from(start.micros() + GameConstants::default().min_run_duration(12));
+    let tournament_certificate = player_chain
+        .add_block(|block| {
+            block.with_timestamp(end);
+            block.with_operation(
+                application_id,
+                Operation::SubmitTournamentScore {
+                    tournament_id: "tournament_0".to_string(),
+                    username: "alice".to_string(),
+                    score: 12,
+                    tap_frames: None,
+                    ticket_id: 2,
+                },
+            );
+        })
+        .await;
+    let challenge_certificate = player_chain
+        .add_block(|block| {
+            block.with_timestamp(end);
+            block.with_operation(
+                application_id,
+                Operation::ChallengePlayer {
+                    username: "alice".to_string(),
+                    opponent: "bob".to_string(),
+                    seed: 42,
+                    deadline: 4_000_000_000,
+                },
+            );
+        })
+        .await;
+    receive_messages_at(
+        &old_chain,
+        Timestamp::from(60_000_000),
+        &[practice_certificate, tournament_certificate, challenge_certificate],
+    )
+    .await;
+
+    let move_leaderboard = || Operation::MoveLeaderboardChain {
+        new_leaderboard_chain_id: new_chain.id(),
+    };
+    let users_certificate = old_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(61_000_000));
+            block.with_operation(application_id, move_leaderboard());
+        })
+        .await;
+
+    // A better run arriving during the move is held, and the old chain takes no other changes
+    let certificate = play_practice_run(
+        &player_chain,
+        application_id,
+        "alice",
+        7,
+        None,
+        Timestamp::from(120_000_000),
+    )
+    .await;
+    receive_messages_at(&old_chain, Timestamp::from(180_000_000), &[certificate]).await;
+
+    let result = old_chain
+        .try_add_block(|block| {
+            block.with_timestamp(Timestamp::from(180_000_000));
+            block.with_operation(
+                application_id,
+                Operation::DeleteTournament {
+                    caller_chain_id: old_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                },
+            );
+        })
+        .await;
+    assert!(result.is_err());
+
+    let mut certificates = vec![users_certificate];
+    for seconds in 181..=186 {
+        let certificate = old_chain
+            .add_block(|block| {
+                block.with_timestamp(Timestamp::from(seconds * 1_000_000));
+                block.with_operation(application_id, move_leaderboard());
+            })
+            .await;
+        certificates.push(certificate);
+    }
+
+    let move_query = "query { isLeaderboardChain leaderboardChainId leaderboardMove { step startedBy } }";
+    let QueryOutcome { response, .. } = old_chain.graphql_query(application_id, move_query).await;
+    assert_eq!(response["leaderboardMove"]["step"], "AWAITING_NEW_CHAIN");
+    assert_eq!(
+        response["leaderboardMove"]["startedBy"],
+        AccountOwner::from(old_chain.public_key()).to_string()
+    );
+
+    // The new chain takes over once it has the boards, then the old chain passes on what it held
+    let accepted =
+        receive_messages_at(&new_chain, Timestamp::from(187_000_000), &certificates).await;
+    let completed = receive_messages_at(&old_chain, Timestamp::from(188_000_000), &[accepted]).await;
+
+    let QueryOutcome { response, .. } = old_chain.graphql_query(application_id, move_query).await;
+    assert_eq!(response["isLeaderboardChain"], false);
+    assert_eq!(response["leaderboardChainId"], new_chain.id().to_string());
+    assert_eq!(response["leaderboardMove"]["step"], "COMPLETED");
+
+    receive_messages_at(&new_chain, Timestamp::from(189_000_000), &[completed]).await;
+
+    let board_query = "query { isLeaderboardChain practiceLeaderboard { username score } \
+        tournaments { id name participants } }";
+    let QueryOutcome { response, .. } = new_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["isLeaderboardChain"], true);
+    assert_eq!(response["practiceLeaderboard"][0]["username"], "alice");
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 7);
+    assert_eq!(response["tournaments"][0]["name"], "Moving Cup");
+    assert_eq!(response["tournaments"][0]["participants"], serde_json::json!(["alice"]));
+
+    // Open challenges and scores waiting for review moved too
+    let records_query = "query { challenges(username: \"bob\") { challenger seed status } \
+        pendingScores { username score tournamentId } }";
+    let QueryOutcome { response, .. } = new_chain.graphql_query(application_id, records_query).await;
+    assert_eq!(response["challenges"][0]["challenger"], "alice");
+    assert_eq!(response["challenges"][0]["seed"], 42);
+    assert_eq!(response["challenges"][0]["status"], "PENDING");
+    assert_eq!(response["pendingScores"][0]["username"], "alice");
+    assert_eq!(response["pendingScores"][0]["score"], 12);
+    assert_eq!(response["pendingScores"][0]["tournamentId"], "tournament_0");
+
+    // Accounts and admin sessions moved along, logins sent to the old chain are passed on
+    let request = player_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(189_000_000));
+            block.with_operation(
+                application_id,
+                Operation::LoginOrRegister {
+                    username: "alice".to_string(),
+                    hash: "alice_hash".to_string(),
+                },
+            );
+        })
+        .await;
+    let forwarded = receive_messages_at(&old_chain, Timestamp::from(189_000_000), &[request]).await;
+    receive_messages_at(&new_chain, Timestamp::from(189_000_000), &[forwarded]).await;
+    new_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(189_000_000));
+            block.with_operation(
+                application_id,
+                Operation::PinTournament {
+                    caller_chain_id: old_chain.id(),
+                    tournament_id: "tournament_0".to_string(),
+                    pin: true,
+                },
+            );
+        })
+        .await;
+    let login_query = format!(
+        "query {{ loginResultFor(chainId: \"{}\") {{ success isNewUser }} }}",
+        player_chain.id()
+    );
+    let QueryOutcome { response, .. } = new_chain.graphql_query(application_id, login_query).await;
+    assert_eq!(response["loginResultFor"]["success"], true);
+    assert_eq!(response["loginResultFor"]["isNewUser"], false);
+
+    // Player chains follow the move and rebuild their replica from the new chain
+    spectator_chain.handle_new_events().await;
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, "query { leaderboardChainId }").await;
+    assert_eq!(response["leaderboardChainId"], new_chain.id().to_string());
+
+    spectator_chain.handle_new_events().await;
+    let QueryOutcome { response, .. } =
+        spectator_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 7);
+    assert_eq!(response["tournaments"][0]["name"], "Moving Cup");
+}
+
+/// Tests stopping leaderboard chain moves
+///
+/// A chain that doesn't follow the leaderboard chain turns the move down, and the owner can
+/// cancel a move; either way the messages held meanwhile are handled on the old chain.
+#[tokio::test(flavor = "multi_thread")]
+async fn leaderboard_move_cancel_test() {
+    let (validator, module_id) =
+        TestValidator::with_current_module::<flappy::FlappyAbi, ApplicationParameters, InstantiationArgument>().await;
+    let mut leaderboard_chain = validator.new_chain().await;
+    let stranger_chain = validator.new_chain().await;
+    let player_chain = validator.new_chain().await;
+
+    let args = InstantiationArgument {
+        player_name: "leaderboard".to_string(),
+        admin_username: Some("admin".to_string()),
+        admin_hash: Some("admin_hash".to_string()),
+    };
+    let parameters = ApplicationParameters {
+        admin_owners: vec![AccountOwner::from(leaderboard_chain.public_key())],
+        ..Default::default()
+    };
+    let application_id = leaderboard_chain
+        .create_application(module_id, parameters, args, vec![])
+        .await;
+
+    for chain in [&leaderboard_chain, &player_chain] {
+        chain
+            .add_block(|block| {
+                block.with_operation(
+                    application_id,
+                    Operation::SetupGame {
+                        leaderboard_chain_id: leaderboard_chain.id(),
+                        leaderboard_name: "leaderboard".to_string(),
+                    },
+                );
+            })
+            .await;
+    }
+    login_from(&player_chain, &leaderboard_chain, application_id, "alice", "alice_hash", Timestamp::from(0))
+        .await;
+
+    // The stranger chain never set the game up, so it turns the move down
+    let move_certificate = leaderboard_chain
+        .add_block(|block| {
+            block.with_operation(
+                application_id,
+                Operation::MoveLeaderboardChain {
+                    new_leaderboard_chain_id: stranger_chain.id(),
+                },
+            );
+        })
+        .await;
+    let certificate =
+        play_practice_run(&player_chain, application_id, "alice", 5, None, Timestamp::from(0)).await;
+    receive_messages_at(&leaderboard_chain, Timestamp::from(60_000_000), &[certificate]).await;
+
+    let board_query = "query { isLeaderboardChain leaderboardMove { step } practiceLeaderboard { score } }";
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["leaderboardMove"]["step"], "TOURNAMENTS");
+    assert_eq!(response["practiceLeaderboard"], serde_json::json!([]));
+
+    let rejection = receive_messages_at(&stranger_chain, Timestamp::from(0), &[move_certificate]).await;
+    receive_messages_at(&leaderboard_chain, Timestamp::from(60_000_000), &[rejection]).await;
+
+    // The held run was handled once the move stopped
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert_eq!(response["isLeaderboardChain"], true);
+    assert!(response["leaderboardMove"].is_null());
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 5);
+
+    let QueryOutcome { response, .. } = stranger_chain
+        .graphql_query(application_id, "query { isLeaderboardChain }")
+        .await;
+    assert_eq!(response["isLeaderboardChain"], false);
+
+    // The admin key cancels a move the same way
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(60_000_000));
+            block.with_operation(
+                application_id,
+                Operation::MoveLeaderboardChain {
+                    new_leaderboard_chain_id: player_chain.id(),
+                },
+            );
+        })
+        .await;
+    let certificate = play_practice_run(
+        &player_chain,
+        application_id,
+        "alice",
+        6,
+        None,
+        Timestamp::from(120_000_000),
+    )
+    .await;
+    receive_messages_at(&leaderboard_chain, Timestamp::from(180_000_000), &[certificate]).await;
+    leaderboard_chain
+        .add_block(|block| {
+            block.with_timestamp(Timestamp::from(180_000_000));
+            block.with_operation(application_id, Operation::CancelLeaderboardMove);
+        })
+        .await;
+
+    let QueryOutcome { response, .. } = leaderboard_chain.graphql_query(application_id, board_query).await;
+    assert!(response["leaderboardMove"].is_null());
+    assert_eq!(response["practiceLeaderboard"][0]["score"], 6);
+}
@@ -224,19 +224,19 @@ export class AuthManager {
     }
 
     try {
-      // Verify current password by attempting login
       const currentHash = await this.generateHash(username, currentPassword);
-      await this.lineraClient.loginOrRegister(username, currentHash);
-      
-      const currentLoginResult = await this.lineraClient.getLoginResult();
-      if (!currentLoginResult || !currentLoginResult.success) {
-        throw new Error('Current password is incorrect');
-      }
-
-      // Change password by registering with new hash
       const newHash = await this.generateHash(username, newPassword);
+      await this.lineraClient.changeCredential(username, currentHash, newHash);
+
+      // Changing the credential ends all sessions, so log back in with the new one
       await this.lineraClient.loginOrRegister(username, newHash);
-      
+      const loginResult = await this.lineraClient.getLoginResult();
+      if (!loginResult || !loginResult.success) {
+        throw new Error('Login with the new password failed');
+      }
+
+      this.currentUser.credentialsHash = newHash;
+      this.saveSession();
       return true;
     } catch (error) {
       throw new Error('Failed to change password: ' + error.message);
@@ -229,6 +229,7 @@ export class LineraClient {
 
     try {
       const response = await this.counter.query(JSON.stringify(queryObject));
+      await this.followLeaderboardChain();
       return response;
     } catch (error) {
       console.error("Failed to setup game:", error);
@@ -236,77 +237,17 @@ export class LineraClient {
     }
   }
 
-  async requestLeaderboard() {
-    if (!this.counter) {
-      throw new Error("Client not initialized");
-    }
-
-    const requestQuery = {
-      query: `
-        mutation {
-          requestLeaderboard
-        }
-      `,
-    };
-
-    try {
-      const response = await this.counter.query(JSON.stringify(requestQuery));
-      return response;
-    } catch (error) {
-      console.error("Failed to request leaderboard:", error);
-      throw error;
-    }
-  }
-
-  async getLeaderboard() {
-    if (!this.counter) {
-      throw new Error("Client not initialized");
-    }
-
-    const leaderboardQuery = {
-      query: `
-        query {
-          topLeaderboard {
-            playerName
-            score
-            chainId
-            timestamp
-          }
-        }
-      `,
-    };
-
-    try {
-      const response = await this.counter.query(
-        JSON.stringify(leaderboardQuery)
-      );
-      return JSON.parse(response);
-    } catch (error) {
-      console.error("Failed to get leaderboard:", error);
-      throw error;
-    }
-  }
-
-  async submitScore(score) {
-    if (!this.counter) {
-      throw new Error("Client not initialized");
+  // Switches to the leaderboard chain this player chain follows, in case it
+  // was moved away from the one configured in .env
+  async followLeaderboardChain() {
+    const data = await this.queryLocalChain(`query { leaderboardChainId }`);
+    const leaderboardChainId = data?.leaderboardChainId;
+    if (!leaderboardChainId || leaderboardChainId === this.LEADERBOARD_CHAIN_ID) {
+      return;
     }
 
-    const queryObject = {
-      query: `
-        mutation {
-          setBestAndSubmit(best: ${score})
-        }
-      `,
-    };
-
-    try {
-      const response = await this.counter.query(JSON.stringify(queryObject));
-      return response;
-    } catch (error) {
-      console.error("Failed to submit score:", error);
-      throw error;
-    }
+    this.LEADERBOARD_CHAIN_ID = leaderboardChainId;
+    this.LEADERBOARD_CHAIN_FULL_URL = `${this.LEADERBOARD_CHAIN_BASE_URL}/chains/${this.LEADERBOARD_CHAIN_ID}/applications/${this.APP_ID}`;
   }
 
   getMnemonic() {
@@ -361,23 +302,197 @@ export class LineraClient {
     }
   }
 
+  // Helper method to query this player chain, which keeps a replica of the
+  // leaderboard chain's top boards and tournaments from its event stream
+  async queryLocalChain(query) {
+    try {
+      const response = await this.counter.query(JSON.stringify({ query }));
+      const data = JSON.parse(response);
+
+      if (data.errors) {
+        throw new Error(`GraphQL errors: ${JSON.stringify(data.errors)}`);
+      }
+
+      return data.data;
+    } catch (error) {
+      console.error("Failed to query local chain:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Re-run a query on the leaderboard chain whenever it adds a block.
+   * The application serves snapshots only; the node service's notifications
+   * subscription tells when to fetch a fresh one.
+   * @param {string} query - Query document
+   * @param {Function} onData - Called with the query data
+   * @returns {Function} Unsubscribe function
+   */
+  subscribeLeaderboardChain(query, onData) {
+    const wsUrl = `${this.LEADERBOARD_CHAIN_BASE_URL.replace(/^http/, "ws")}/ws`;
+    const socket = new WebSocket(wsUrl, "graphql-transport-ws");
+    let closed = false;
+
+    const refresh = async () => {
+      try {
+        const data = await this.queryLeaderboardChain(query);
+        if (!closed) {
+          onData(data);
+        }
+      } catch (error) {
+        console.error("Failed to refresh subscription:", error);
+      }
+    };
+
+    socket.onopen = () => {
+      socket.send(JSON.stringify({ type: "connection_init" }));
+    };
+
+    socket.onmessage = (event) => {
+      const message = JSON.parse(event.data);
+      if (message.type === "connection_ack") {
+        socket.send(
+          JSON.stringify({
+            id: "1",
+            type: "subscribe",
+            payload: {
+              query: `subscription { notifications(chainId: "${this.LEADERBOARD_CHAIN_ID}") }`,
+            },
+          })
+        );
+        refresh();
+      } else if (message.type === "next") {
+        refresh();
+      } else if (message.type === "ping") {
+        socket.send(JSON.stringify({ type: "pong" }));
+      }
+    };
+
+    socket.onerror = (error) => {
+      console.error("Leaderboard chain subscription error:", error);
+    };
+
+    return () => {
+      closed = true;
+      socket.close();
+    };
+  }
+
   // ==========================================
   // AUTHENTICATION METHODS
   // ==========================================
 
   /**
-   * Login or register user on leaderboard chain
+   * Login or register user from this chain. The chain sends the request to the
+   * leaderboard chain, which checks it and replies with the result, so this
+   * waits until the reply has reached this chain.
    * @param {string} username - Username
    * @param {string} hash - Password hash
    * @returns {Promise} Login/register result
    */
   async loginOrRegister(username, hash) {
+    if (!this.counter) {
+      throw new Error("Client not initialized");
+    }
+
+    const queryObject = {
+      query: `
+        mutation {
+          loginOrRegister(
+            username: "${username}",
+            hash: "${hash}"
+          )
+        }
+      `,
+    };
+
+    try {
+      await this.counter.query(JSON.stringify(queryObject));
+
+      // The chain clears its last result when it sends the request
+      for (let attempt = 0; attempt < 30; attempt++) {
+        const result = await this.getLoginResult();
+        if (result) {
+          return result;
+        }
+        await new Promise((resolve) => setTimeout(resolve, 500));
+      }
+      throw new Error("Timed out waiting for the login result");
+    } catch (error) {
+      console.error("Failed to login/register:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Change the credential hash of the logged-in user
+   * @param {string} username - Username
+   * @param {string} oldHash - Current credential hash
+   * @param {string} newHash - New credential hash
+   * @returns {Promise} Change result
+   */
+  async changeCredential(username, oldHash, newHash) {
+    const query = `
+      mutation {
+        changeCredential(
+          callerChainId: "${this.chainId}",
+          username: "${username}",
+          oldHash: "${oldHash}",
+          newHash: "${newHash}"
+        )
+      }
+    `;
+
+    try {
+      await this.queryLeaderboardChain(query);
+      return { success: true };
+    } catch (error) {
+      console.error("Failed to change credential:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Set a new credential hash using a one-time reset token from an admin
+   * @param {string} username - Username
+   * @param {string} token - Reset token
+   * @param {string} newHash - New credential hash
+   * @returns {Promise} Reset result
+   */
+  async resetCredential(username, token, newHash) {
+    const query = `
+      mutation {
+        resetCredential(
+          username: "${username}",
+          token: "${token}",
+          newHash: "${newHash}"
+        )
+      }
+    `;
+
+    try {
+      await this.queryLeaderboardChain(query);
+      return { success: true };
+    } catch (error) {
+      console.error("Failed to reset credential:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Delete the user's own account
+   * @param {string} username - Username
+   * @param {string} hash - Credential hash of the account
+   * @param {boolean} purgeScores - Remove scores permanently instead of hiding them
+   * @returns {Promise} Deletion result
+   */
+  async deleteMyAccount(username, hash, purgeScores = false) {
     const query = `
       mutation {
-        loginOrRegister(
+        deleteMyAccount(
           username: "${username}",
           hash: "${hash}",
-          requesterChainId: "${this.chainId}"
+          purgeScores: ${purgeScores}
         )
       }
     `;
@@ -386,7 +501,28 @@ export class LineraClient {
       await this.queryLeaderboardChain(query);
       return { success: true };
     } catch (error) {
-      console.error("Failed to login/register:", error);
+      console.error("Failed to delete account:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Export the data of the user logged in on this chain through loginOrRegister
+   * @param {string} username - Username
+   * @returns {Promise<Object>} User data bundle
+   */
+  async exportUserData(username) {
+    const query = `
+      query {
+        exportUserData(username: "${username}")
+      }
+    `;
+
+    try {
+      const data = await this.queryLocalChain(query);
+      return data.exportUserData;
+    } catch (error) {
+      console.error("Failed to export user data:", error);
       throw error;
     }
   }
@@ -413,7 +549,7 @@ export class LineraClient {
     `;
 
     try {
-      const data = await this.queryLeaderboardChain(query);
+      const data = await this.queryLocalChain(query);
       return data.loginResultFor;
     } catch (error) {
       console.error("Failed to get login result:", error);
@@ -422,7 +558,8 @@ export class LineraClient {
   }
 
   /**
-   * Get current user info
+   * Get the user logged in on this chain, set once the leaderboard chain's
+   * reply to loginOrRegister reaches it
    * @returns {Promise} User info
    */
   async getCurrentUser() {
@@ -435,7 +572,7 @@ export class LineraClient {
     `;
 
     try {
-      const data = await this.queryLeaderboardChain(query);
+      const data = await this.queryLocalChain(query);
       if (data.isLoggedIn) {
         return {
           username: data.username,
@@ -454,13 +591,65 @@ export class LineraClient {
   // PRACTICE MODE METHODS
   // ==========================================
 
+  /**
+   * Start a run, issuing the ticket its score must be submitted with
+   * @returns {Promise<Object|null>} Run ticket ({ id, issuedAt })
+   */
+  async startRun() {
+    if (!this.counter) {
+      throw new Error("Client not initialized");
+    }
+
+    const queryObject = {
+      query: `
+        mutation {
+          startRun
+        }
+      `,
+    };
+
+    try {
+      await this.counter.query(JSON.stringify(queryObject));
+      return await this.getOpenRunTicket();
+    } catch (error) {
+      console.error("Failed to start run:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Get the ticket of the run in progress on this chain
+   * @returns {Promise<Object|null>} Run ticket ({ id, issuedAt })
+   */
+  async getOpenRunTicket() {
+    const query = `
+      query {
+        openRunTicket {
+          id
+          issuedAt
+        }
+      }
+    `;
+
+    try {
+      const response = await this.counter.query(JSON.stringify({ query }));
+      const data = JSON.parse(response);
+      return data.data?.openRunTicket || null;
+    } catch (error) {
+      console.error("Failed to get run ticket:", error);
+      return null;
+    }
+  }
+
   /**
    * Submit practice score
    * @param {string} username - Username
    * @param {number} score - Score to submit
+   * @param {number} ticketId - Ticket issued by startRun for this run
+   * @param {number[]|null} tapFrames - Frame indices of each tap, stored as a ghost run
    * @returns {Promise} Submission result
    */
-  async submitPracticeScore(username, score) {
+  async submitPracticeScore(username, score, ticketId, tapFrames = null) {
     if (!this.counter) {
       throw new Error("Client not initialized");
     }
@@ -470,7 +659,9 @@ export class LineraClient {
         mutation {
           submitPracticeScore(
             username: "${username}",
-            score: ${score}
+            score: ${score},
+            tapFrames: ${tapFrames ? JSON.stringify(tapFrames) : "null"},
+            ticketId: ${ticketId}
           )
         }
       `,
@@ -502,7 +693,7 @@ export class LineraClient {
     `;
 
     try {
-      const data = await this.queryLeaderboardChain(query);
+      const data = await this.queryLocalChain(query);
       return data.practiceLeaderboard;
     } catch (error) {
       console.error("Failed to get practice leaderboard:", error);
@@ -510,6 +701,56 @@ export class LineraClient {
     }
   }
 
+  /**
+   * Re-fetch the practice leaderboard on every new leaderboard chain block
+   * @param {Function} onData - Called with the practice leaderboard
+   * @returns {Function} Unsubscribe function
+   */
+  subscribePracticeLeaderboard(onData) {
+    const query = `
+      query {
+        practiceLeaderboard {
+          username
+          score
+          chainId
+          timestamp
+        }
+      }
+    `;
+
+    return this.subscribeLeaderboardChain(query, (data) =>
+      onData(data.practiceLeaderboard || [])
+    );
+  }
+
+  /**
+   * Get the ghost run of a player's best run
+   * @param {string} username - Username
+   * @param {string} context - "practice" or a tournament ID
+   * @returns {Promise} Ghost run with tap frames, or null
+   */
+  async getGhostRun(username, context = "practice") {
+    const query = `
+      query {
+        ghostRun(username: "${username}", context: "${context}") {
+          username
+          context
+          score
+          tapFrames
+          recordedAt
+        }
+      }
+    `;
+
+    try {
+      const data = await this.queryLeaderboardChain(query);
+      return data.ghostRun;
+    } catch (error) {
+      console.error("Failed to get ghost run:", error);
+      throw error;
+    }
+  }
+
   /**
    * Get my practice scores and best
    * @returns {Promise} My practice data
@@ -534,6 +775,65 @@ export class LineraClient {
     }
   }
 
+  /**
+   * Get the player's standing from this chain's replica of the leaderboard chain
+   * @param {string} username - Username
+   * @returns {Promise} Practice rank, best score and tournament profile
+   */
+  async getPlayerStanding(username) {
+    const query = `
+      query {
+        myRank
+        playerProfile(username: "${username}") {
+          bestScore
+          practiceRank
+          tournamentsJoined
+          tournamentWins
+          tournamentPlacements {
+            tournamentId
+            tournamentName
+            status
+            bestScore
+            rank
+          }
+        }
+      }
+    `;
+
+    try {
+      const data = await this.queryLocalChain(query);
+      return {
+        myRank: data.myRank,
+        profile: data.playerProfile,
+      };
+    } catch (error) {
+      console.error("Failed to get player standing:", error);
+      throw error;
+    }
+  }
+
+  /**
+   * Get the rank of the user logged in on this chain in a tournament, from this
+   * chain's replica. Needs loginOrRegister to have completed on this chain
+   * @param {string} tournamentId - Tournament ID
+   * @returns {Promise<number|null>} Rank, null if not ranked
+   */
+  async getMyTournamentRank(tournamentId) {
+    const query = `
+      query {
+        myTournamentRank(tournamentId: "${tournamentId}")
+      }
+    `;
+
+    try {
+      const data = await this.queryLocalChain(query);
+      return data.myTournamentRank;
+    } catch (error) {
+      console.error("Failed to get tournament rank:", error);
+      throw error;
+    }
+  }
+
   // ==========================================
   // TOURNAMENT BLOCKCHAIN METHODS
   // ==========================================
@@ -604,7 +904,7 @@ export class LineraClient {
     `;
 
     try {
-      const data = await this.queryLeaderboardChain(query);
+      const data = await this.queryLocalChain(query);
       const tournaments = data.tournaments || [];
 
       // Fetch participant count and max score for each tournament
@@ -619,7 +919,7 @@ export class LineraClient {
                 tournamentParticipantCount(tournamentId: "${tournament.id}")
               }
             `;
-            const countData = await this.queryLeaderboardChain(countQuery);
+            const countData = await this.queryLocalChain(countQuery);
             playerCount = countData.tournamentParticipantCount || 0;
           } catch (error) {
             console.warn(
@@ -699,9 +999,11 @@ export class LineraClient {
    * @param {string} tournamentId - Tournament ID
    * @param {string} username - Username
    * @param {number} score - Player score
+   * @param {number} ticketId - Ticket issued by startRun for this run
+   * @param {number[]|null} tapFrames - Frame indices of each tap, stored as a ghost run
    * @returns {Promise} Score submission result
    */
-  async submitTournamentScore(tournamentId, username, score) {
+  async submitTournamentScore(tournamentId, username, score, ticketId, tapFrames = null) {
     if (!this.counter) {
       throw new Error("Client not initialized");
     }
@@ -712,7 +1014,9 @@ export class LineraClient {
           submitTournamentScore(
             tournamentId: "${tournamentId}",
             username: "${username}",
-            score: ${score}
+            score: ${score},
+            tapFrames: ${tapFrames ? JSON.stringify(tapFrames) : "null"},
+            ticketId: ${ticketId}
           )
         }
       `,
@@ -784,6 +1088,30 @@ export class LineraClient {
     }
   }
 
+  /**
+   * Re-fetch a tournament leaderboard on every new leaderboard chain block
+   * @param {string} tournamentId - Tournament ID
+   * @param {Function} onData - Called with the tournament leaderboard
+   * @returns {Function} Unsubscribe function
+   */
+  subscribeTournamentLeaderboard(tournamentId, onData) {
+    const query = `
+      query {
+        tournamentLeaderboard(tournamentId: "${tournamentId}") {
+          username
+          score
+          rank
+          chainId
+          timestamp
+        }
+      }
+    `;
+
+    return this.subscribeLeaderboardChain(query, (data) =>
+      onData(data.tournamentLeaderboard || [])
+    );
+  }
+
   /**
    * Alias for getTournamentLeaderboard for compatibility
    * @param {string} tournamentId - Tournament ID
@@ -154,8 +154,6 @@ export const BLOCKCHAIN_CONFIG = {
   OPERATIONS: {
     SETUP_GAME: "setupGame",
     INCREMENT: "increment",
-    SET_BEST_AND_SUBMIT: "setBestAndSubmit",
-    REQUEST_LEADERBOARD: "requestLeaderboard",
   },
 };
 
@@ -432,13 +432,14 @@ export class GameState {
     }
   }
 
-  async submitTournamentScore(tournamentId, score) {
+  async submitTournamentScore(tournamentId, score, ticketId) {
     try {
       // Submit tournament score to blockchain
       const result = await this.blockchainClient.submitTournamentScore(
         tournamentId,
         this.playerName,
-        score
+        score,
+        ticketId
       );
 
       // Reload tournaments from blockchain to get updated data
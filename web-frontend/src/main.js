@@ -387,6 +387,7 @@ Please check:
     }
 
     this.gameUI.hideStartButton();
+    this.startRunTicket();
     // Enable game controls and start the game
     this.gameEngine.enableGameControls();
   }
@@ -417,11 +418,29 @@ Please check:
 
     this.gameEngine.resetGameState();
     this.gameUI.hideRestartButton();
+    this.startRunTicket();
     this.gameEngine.startGameLoop();
     // Immediately enable game controls for restart (no start button needed)
     this.gameEngine.enableGameControls();
   }
 
+  // Each run needs a ticket from the chain before its score can be submitted
+  async startRunTicket() {
+    this.currentRunTicket = null;
+    try {
+      this.currentRunTicket = await this.lineraClient.startRun();
+    } catch (error) {
+      console.error("Failed to start run:", error);
+    }
+  }
+
+  // A ticket covers a single submission
+  takeRunTicketId() {
+    const ticketId = this.currentRunTicket?.id;
+    this.currentRunTicket = null;
+    return ticketId;
+  }
+
   handleJump() {
     if (this.gameState.getCurrentScreen() === "game-screen") {
       // Check if we're in tournament mode and tournament is not yet active
@@ -469,7 +488,8 @@ Please check:
           console.log("isPlayerInTournament");
           await this.gameState.submitTournamentScore(
             activeTournament.id,
-            score
+            score,
+            this.takeRunTicketId()
           );
           // Refresh tournament leaderboard to update best score display
           await this.loadTournamentLeaderboard(activeTournament.id);
@@ -1141,10 +1161,7 @@ Please check:
     try {
       const gameMode = this.gameState.getGameMode();
 
-      if (gameMode === "practice") {
-        // Refresh practice leaderboard
-        await this.loadPracticeLeaderboard();
-      } else if (gameMode === "tournament") {
+      if (gameMode === "tournament") {
         // Refresh tournament leaderboard for current tournament
         const activeTournament = this.gameState.getActiveTournament();
         if (activeTournament && activeTournament.id) {
@@ -1153,41 +1170,14 @@ Please check:
           console.warn("No active tournament to refresh leaderboard for");
         }
       } else {
-        // Refresh legacy leaderboard
-        await this.loadLegacyLeaderboard();
+        // Refresh practice leaderboard
+        await this.loadPracticeLeaderboard();
       }
     } catch (error) {
       console.error("Failed to refresh leaderboard:", error);
     }
   }
 
-  async loadLegacyLeaderboard() {
-    try {
-      // Request updated leaderboard
-      await this.lineraClient.requestLeaderboard();
-
-      // Get updated leaderboard
-      const leaderboardResponse = await this.lineraClient.getLeaderboard();
-      const leaderboard = leaderboardResponse.data.topLeaderboard || [];
-
-      // Update game state
-      this.gameState.setLeaderboard(leaderboard);
-
-      // Find player's rank
-      const playerRank =
-        leaderboard.findIndex(
-          (entry) => entry.playerName === this.gameState.getPlayerName()
-        ) + 1;
-
-      if (playerRank > 0) {
-        this.gameState.setMyRank(playerRank);
-        this.gameEngine.setMyRank(playerRank);
-      }
-    } catch (error) {
-      console.error("Failed to load legacy leaderboard:", error);
-    }
-  }
-
   selectTournamentMode() {
     this.gameState.setGameMode("tournament");
     this.gameState.setCurrentScreen("tournament-screen");
@@ -1292,18 +1282,14 @@ Please check:
 
       const gameMode = this.gameState.getGameMode();
 
-      if (gameMode === "practice") {
-        // Handle practice mode score submission
-        await this.submitPracticeScore(score);
-        this.refreshLeaderboard();
-      } else if (gameMode === "tournament") {
+      if (gameMode === "tournament") {
         // Handle tournament mode score submission
         await this.submitTournamentScore(score);
-        this.refreshLeaderboard();
       } else {
-        // Fallback to legacy leaderboard system
-        await this.submitLegacyScore(score);
+        // Handle practice mode score submission
+        await this.submitPracticeScore(score);
       }
+      this.refreshLeaderboard();
     } catch (error) {
       console.error("Failed to submit score:", error);
     }
@@ -1317,7 +1303,8 @@ Please check:
       // Submit practice score to blockchain
       await this.lineraClient.submitPracticeScore(
         this.gameState.getPlayerName(),
-        score
+        score,
+        this.takeRunTicketId()
       );
 
       spinner.updateMessage("Updating practice statistics...");
@@ -1382,7 +1369,8 @@ Please check:
         await this.lineraClient.submitTournamentScore(
           activeTournament.id,
           this.gameState.getPlayerName(),
-          score
+          score,
+          this.takeRunTicketId()
         );
 
         spinner.updateMessage("Updating tournament statistics...");
@@ -1405,33 +1393,6 @@ Please check:
     }
   }
 
-  async submitLegacyScore(score) {
-    // Legacy leaderboard system (keep for backward compatibility)
-    // Game should already be set up at login, but check just in case
-    if (!this.gameState.isGameConfigurationComplete()) {
-      await this.setupBlockchainGame();
-    }
-
-    await this.lineraClient.submitScore(score);
-    await this.lineraClient.requestLeaderboard();
-
-    const leaderboardResponse = await this.lineraClient.getLeaderboard();
-    const leaderboard = leaderboardResponse.data.topLeaderboard || [];
-
-    this.gameState.setLeaderboard(leaderboard);
-
-    const playerRank =
-      leaderboard.findIndex(
-        (entry) => entry.playerName === this.gameState.getPlayerName()
-      ) + 1;
-
-    if (playerRank > 0) {
-      this.gameState.setMyRank(playerRank);
-      this.gameEngine.setMyRank(playerRank);
-      this.gameUI.updateMyPosition(playerRank, score);
-    }
-  }
-
   // Tournament management methods
   async joinTournament(tournamentId) {
     try {
@@ -11,6 +11,7 @@ export class TournamentModal {
     this.best = 0;
     this.lineraClient = lineraClient;
     this.currentTournament = null; // Store the currently displayed tournament
+    this.unsubscribeLeaderboard = null; // Stops live leaderboard updates
     
     this.initializeElements();
   }
@@ -72,6 +73,9 @@ export class TournamentModal {
 
       // Set up join button
       await this.setupJoinTournamentButton(tournament);
+
+      // Keep the leaderboard live while the modal is open
+      this.subscribeToTournamentLeaderboard(tournament);
       
     } finally {
       // Hide loading spinner
@@ -81,6 +85,31 @@ export class TournamentModal {
 
   hideTournamentLeaderboardModal() {
     this.tournamentLeaderboardModal.style.display = "none";
+    this.unsubscribeFromTournamentLeaderboard();
+  }
+
+  subscribeToTournamentLeaderboard(tournament) {
+    this.unsubscribeFromTournamentLeaderboard();
+    if (!this.lineraClient) {
+      return;
+    }
+
+    this.unsubscribeLeaderboard = this.lineraClient.subscribeTournamentLeaderboard(
+      tournament.id,
+      (response) => {
+        const leaderboardData = this.mapTournamentLeaderboard(response);
+        this.renderTournamentLeaderboardEntries(leaderboardData);
+        this.updateMyTournamentPosition(leaderboardData);
+        document.getElementById("modal-tournament-players").textContent = leaderboardData.length;
+      }
+    );
+  }
+
+  unsubscribeFromTournamentLeaderboard() {
+    if (this.unsubscribeLeaderboard) {
+      this.unsubscribeLeaderboard();
+      this.unsubscribeLeaderboard = null;
+    }
   }
 
   updateModalTournamentTimer(tournament) {
@@ -139,19 +168,23 @@ export class TournamentModal {
         return [];
       }
 
-      // Transform blockchain data to match expected format
-      return response.map((entry, index) => ({
-        name: entry.username,
-        score: entry.score,
-        rank: entry.rank || (index + 1),
-        isCurrentPlayer: entry.username === this.playerName
-      }));
+      return this.mapTournamentLeaderboard(response);
     } catch (error) {
       console.error("Error fetching tournament leaderboard:", error);
       throw error;
     }
   }
 
+  // Transform blockchain data to match expected format
+  mapTournamentLeaderboard(response) {
+    return response.map((entry, index) => ({
+      name: entry.username,
+      score: entry.score,
+      rank: entry.rank || (index + 1),
+      isCurrentPlayer: entry.username === this.playerName
+    }));
+  }
+
 
   renderTournamentLeaderboardEntries(leaderboardData) {
     if (!leaderboardData || leaderboardData.length === 0) {